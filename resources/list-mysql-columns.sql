@@ -22,8 +22,14 @@ select distinct
     col.NUMERIC_SCALE,
     -- 欄位型別
     col.COLUMN_TYPE,
+    -- 字元集
+    col.CHARACTER_SET_NAME,
+    -- 定序
+    col.COLLATION_NAME,
     -- 備註
     col.COLUMN_COMMENT,
+    -- 額外屬性
+    col.EXTRA,
     -- 主鍵
     case pk.CONSTRAINT_TYPE
         when 'PRIMARY KEY' then
@@ -0,0 +1,21 @@
+-- MySQL 查詢排程事件
+select
+	e.EVENT_NAME,
+	-- 排程, 單次執行或週期執行
+	case e.EVENT_TYPE
+		when 'ONE TIME' then concat('AT ', e.EXECUTE_AT)
+		else concat_ws(' ',
+			concat('EVERY ', e.INTERVAL_VALUE, ' ', e.INTERVAL_FIELD),
+			concat('STARTS ', e.STARTS),
+			concat('ENDS ', e.ENDS))
+		end as SCHEDULE,
+	e.TIME_ZONE,
+	e.STATUS,
+	e.ON_COMPLETION,
+	e.EVENT_DEFINITION
+from
+	INFORMATION_SCHEMA.EVENTS e
+where
+	e.EVENT_SCHEMA = :schema
+order by
+	e.EVENT_NAME
\ No newline at end of file
@@ -0,0 +1,21 @@
+select
+	kcu.TABLE_NAME,
+	kcu.CONSTRAINT_NAME,
+	kcu.ORDINAL_POSITION,
+	kcu.COLUMN_NAME,
+	kcu.REFERENCED_TABLE_SCHEMA,
+	kcu.REFERENCED_TABLE_NAME,
+	kcu.REFERENCED_COLUMN_NAME,
+	rc.UPDATE_RULE,
+	rc.DELETE_RULE
+from
+	INFORMATION_SCHEMA.REFERENTIAL_CONSTRAINTS rc
+	inner join INFORMATION_SCHEMA.KEY_COLUMN_USAGE kcu on kcu.CONSTRAINT_SCHEMA = rc.CONSTRAINT_SCHEMA
+		and kcu.CONSTRAINT_NAME = rc.CONSTRAINT_NAME
+		and kcu.TABLE_NAME = rc.TABLE_NAME
+where
+	rc.CONSTRAINT_SCHEMA = :schema
+order by
+	kcu.TABLE_NAME,
+	kcu.CONSTRAINT_NAME,
+	kcu.ORDINAL_POSITION
\ No newline at end of file
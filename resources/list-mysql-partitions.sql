@@ -0,0 +1,33 @@
+-- MySQL 查詢分區資料
+select
+	p.TABLE_NAME,
+	p.PARTITION_NAME,
+	p.PARTITION_ORDINAL_POSITION,
+	p.PARTITION_METHOD,
+	p.PARTITION_EXPRESSION,
+	-- 分區邊界, 依分區方式組成語法
+	case
+		when p.PARTITION_METHOD like 'RANGE%' then concat('VALUES LESS THAN (', p.PARTITION_DESCRIPTION, ')')
+		when p.PARTITION_METHOD like 'LIST%' then concat('VALUES IN (', p.PARTITION_DESCRIPTION, ')')
+		end as PARTITION_BOUND,
+	p.SUBPARTITION_METHOD,
+	p.SUBPARTITION_EXPRESSION,
+	-- 子分區數量, 每個子分區一筆資料
+	count(p.SUBPARTITION_NAME) as SUBPARTITIONS
+from
+	INFORMATION_SCHEMA.PARTITIONS p
+where
+	p.TABLE_SCHEMA = :schema
+	and p.PARTITION_NAME is not null
+group by
+	p.TABLE_NAME,
+	p.PARTITION_NAME,
+	p.PARTITION_ORDINAL_POSITION,
+	p.PARTITION_METHOD,
+	p.PARTITION_EXPRESSION,
+	p.PARTITION_DESCRIPTION,
+	p.SUBPARTITION_METHOD,
+	p.SUBPARTITION_EXPRESSION
+order by
+	p.TABLE_NAME,
+	p.PARTITION_ORDINAL_POSITION
\ No newline at end of file
@@ -0,0 +1,26 @@
+-- MySQL 查詢預存程序與函式
+select
+	r.ROUTINE_TYPE,
+	r.ROUTINE_NAME,
+	-- 參數, 依序列出模式 名稱 型別
+	(
+		select
+			group_concat(concat_ws(' ', p.PARAMETER_MODE, p.PARAMETER_NAME, p.DTD_IDENTIFIER) order by p.ORDINAL_POSITION separator ', ')
+		from
+			INFORMATION_SCHEMA.PARAMETERS p
+		where
+			p.SPECIFIC_SCHEMA = r.ROUTINE_SCHEMA
+			and p.SPECIFIC_NAME = r.SPECIFIC_NAME
+			and p.ROUTINE_TYPE = r.ROUTINE_TYPE
+			and p.ORDINAL_POSITION > 0
+	) as PARAMETERS,
+	-- 回傳型別, 只有 function 有
+	case when r.ROUTINE_TYPE = 'FUNCTION' then r.DTD_IDENTIFIER end as RETURNS,
+	r.ROUTINE_DEFINITION
+from
+	INFORMATION_SCHEMA.ROUTINES r
+where
+	r.ROUTINE_SCHEMA = :schema
+order by
+	r.ROUTINE_NAME,
+	r.ROUTINE_TYPE
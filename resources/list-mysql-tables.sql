@@ -0,0 +1,12 @@
+select
+	TABLE_NAME,
+	ENGINE,
+	TABLE_COLLATION,
+	ROW_FORMAT,
+	AUTO_INCREMENT,
+	TABLE_COMMENT
+from
+	INFORMATION_SCHEMA.TABLES
+where
+	TABLE_SCHEMA = :schema
+	and TABLE_TYPE = 'BASE TABLE'
\ No newline at end of file
@@ -0,0 +1,15 @@
+-- MySQL 查詢觸發程序
+select
+	t.EVENT_OBJECT_TABLE,
+	t.TRIGGER_NAME,
+	t.ACTION_TIMING,
+	t.EVENT_MANIPULATION,
+	t.ACTION_STATEMENT,
+	t.DEFINER
+from
+	INFORMATION_SCHEMA.TRIGGERS t
+where
+	t.TRIGGER_SCHEMA = :schema
+order by
+	t.EVENT_OBJECT_TABLE,
+	t.TRIGGER_NAME
\ No newline at end of file
@@ -0,0 +1,23 @@
+-- MySQL 查詢檢視表
+select
+	v.TABLE_NAME,
+	-- 欄位名稱, 依欄位順序
+	(
+		select
+			group_concat(c.COLUMN_NAME order by c.ORDINAL_POSITION separator ', ')
+		from
+			INFORMATION_SCHEMA.COLUMNS c
+		where
+			c.TABLE_SCHEMA = v.TABLE_SCHEMA
+			and c.TABLE_NAME = v.TABLE_NAME
+	) as VIEW_COLUMNS,
+	v.VIEW_DEFINITION,
+	v.CHECK_OPTION,
+	v.SECURITY_TYPE,
+	v.IS_UPDATABLE
+from
+	INFORMATION_SCHEMA.VIEWS v
+where
+	v.TABLE_SCHEMA = :schema
+order by
+	v.TABLE_NAME
\ No newline at end of file
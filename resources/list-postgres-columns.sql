@@ -0,0 +1,53 @@
+-- PostgreSQL 查詢欄位資料
+select
+    -- 表格名稱
+    col.table_name::text as table_name,
+    -- 欄位名稱
+    col.column_name::text as column_name,
+    -- 欄位排序
+    col.ordinal_position::int as ordinal_position,
+    -- 欄位預設值
+    col.column_default::text as column_default,
+    -- 允許空值
+    col.is_nullable::text as is_nullable,
+    -- 資料型別
+    col.data_type::text as data_type,
+    -- 文字長度
+    col.character_maximum_length::int as character_maximum_length,
+    -- 文字長度
+    col.character_octet_length::int as character_octet_length,
+    -- 數值長度
+    col.numeric_precision::int as numeric_precision,
+    -- 數值長度
+    col.numeric_scale::int as numeric_scale,
+    -- 欄位型別
+    format_type(a.atttypid, a.atttypmod) as column_type,
+    -- 字元集
+    col.character_set_name::text as character_set_name,
+    -- 定序
+    col.collation_name::text as collation_name,
+    -- 備註
+    col_description(c.oid, a.attnum) as column_comment,
+    -- 額外屬性
+    case
+        when col.is_identity = 'YES' or col.column_default like 'nextval(%' then
+            'auto_increment'
+        when col.is_generated = 'ALWAYS' then
+            'STORED GENERATED'
+        end as extra,
+    -- 主鍵
+    exists(select 1
+           from pg_catalog.pg_index i
+           where i.indrelid = c.oid
+             and i.indisprimary
+             and a.attnum = any (i.indkey)) as primary_key
+from information_schema.columns col
+         inner join pg_catalog.pg_namespace n on n.nspname = col.table_schema
+         inner join pg_catalog.pg_class c on c.relnamespace = n.oid
+    and c.relname = col.table_name
+         inner join pg_catalog.pg_attribute a on a.attrelid = c.oid
+    and a.attname = col.column_name
+where col.table_schema = $1
+  -- partitions are compared as part of their table
+  and not c.relispartition
+order by col.table_name, col.ordinal_position
\ No newline at end of file
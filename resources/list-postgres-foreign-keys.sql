@@ -0,0 +1,32 @@
+select
+	kcu.table_name::text as table_name,
+	kcu.constraint_name::text as constraint_name,
+	kcu.ordinal_position::int as ordinal_position,
+	kcu.column_name::text as column_name,
+	ref.table_schema::text as referenced_table_schema,
+	ref.table_name::text as referenced_table_name,
+	ref.column_name::text as referenced_column_name,
+	rc.update_rule::text as update_rule,
+	rc.delete_rule::text as delete_rule
+from
+	information_schema.referential_constraints rc
+	inner join information_schema.key_column_usage kcu on kcu.constraint_schema = rc.constraint_schema
+		and kcu.constraint_name = rc.constraint_name
+	inner join information_schema.key_column_usage ref on ref.constraint_schema = rc.unique_constraint_schema
+		and ref.constraint_name = rc.unique_constraint_name
+		and ref.ordinal_position = kcu.position_in_unique_constraint
+where
+	rc.constraint_schema = $1
+	-- partitions are compared as part of their table
+	and not exists (
+		select 1
+		from pg_catalog.pg_class p
+			inner join pg_catalog.pg_namespace pn on pn.oid = p.relnamespace
+		where pn.nspname = kcu.table_schema
+			and p.relname = kcu.table_name
+			and p.relispartition
+	)
+order by
+	kcu.table_name,
+	kcu.constraint_name,
+	kcu.ordinal_position
\ No newline at end of file
@@ -0,0 +1,18 @@
+select
+	t.relname::text as table_name,
+	i.relname::text as index_name,
+	ix.indisunique as is_unique,
+	k.seq_in_index::int as seq_in_index,
+	coalesce(a.attname::text, pg_get_indexdef(ix.indexrelid, k.seq_in_index::int, true)) as column_name
+from
+	pg_catalog.pg_index ix
+	inner join pg_catalog.pg_class t on t.oid = ix.indrelid
+	inner join pg_catalog.pg_class i on i.oid = ix.indexrelid
+	inner join pg_catalog.pg_namespace n on n.oid = t.relnamespace
+	cross join lateral unnest(ix.indkey::int2[]) with ordinality as k(attnum, seq_in_index)
+	left join pg_catalog.pg_attribute a on a.attrelid = t.oid
+		and a.attnum = k.attnum
+where
+	n.nspname = $1
+	-- partitions are compared as part of their table
+	and not t.relispartition
\ No newline at end of file
@@ -0,0 +1,19 @@
+select
+	parent.relname::text as table_name,
+	child.relname::text as partition_name,
+	(row_number() over (partition by parent.oid order by child.relname))::int as ordinal_position,
+	-- pg_get_partkeydef gives e.g. RANGE (created_at)
+	split_part(pg_get_partkeydef(parent.oid), ' ', 1) as partition_method,
+	regexp_replace(pg_get_partkeydef(parent.oid), '^\w+ \((.*)\)$', '\1') as partition_expression,
+	pg_get_expr(child.relpartbound, child.oid) as partition_bound
+from
+	pg_catalog.pg_inherits i
+	inner join pg_catalog.pg_class parent on parent.oid = i.inhparent
+	inner join pg_catalog.pg_class child on child.oid = i.inhrelid
+	inner join pg_catalog.pg_namespace n on n.oid = parent.relnamespace
+where
+	n.nspname = $1
+	and parent.relkind = 'p'
+order by
+	table_name,
+	ordinal_position
\ No newline at end of file
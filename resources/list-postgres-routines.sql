@@ -0,0 +1,15 @@
+select
+	case p.prokind when 'p' then 'PROCEDURE' else 'FUNCTION' end as routine_type,
+	p.proname::text || '(' || pg_get_function_identity_arguments(p.oid) || ')' as routine_name,
+	pg_get_function_arguments(p.oid) as parameters,
+	case p.prokind when 'p' then null else pg_get_function_result(p.oid) end as returns,
+	pg_get_functiondef(p.oid) as definition
+from
+	pg_catalog.pg_proc p
+	inner join pg_catalog.pg_namespace n on n.oid = p.pronamespace
+where
+	n.nspname = $1
+	and p.prokind in ('f', 'p')
+order by
+	routine_name,
+	routine_type
@@ -0,0 +1,24 @@
+select
+	t.table_name::text as table_name,
+	am.amname::text as engine,
+	null::text as table_collation,
+	null::text as row_format,
+	exists (
+		select 1
+		from information_schema.columns col
+		where col.table_schema = t.table_schema
+			and col.table_name = t.table_name
+			and (col.is_identity = 'YES' or col.column_default like 'nextval(%')
+	) as auto_increment,
+	obj_description(c.oid, 'pg_class') as table_comment
+from
+	information_schema.tables t
+	inner join pg_catalog.pg_namespace n on n.nspname = t.table_schema
+	inner join pg_catalog.pg_class c on c.relnamespace = n.oid
+		and c.relname = t.table_name
+	left join pg_catalog.pg_am am on am.oid = c.relam
+where
+	t.table_schema = $1
+	and t.table_type = 'BASE TABLE'
+	-- partitions are compared as part of their table
+	and not c.relispartition
\ No newline at end of file
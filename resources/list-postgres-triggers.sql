@@ -0,0 +1,19 @@
+select
+	t.event_object_table::text as event_object_table,
+	t.trigger_name::text as trigger_name,
+	t.action_timing::text as action_timing,
+	-- one row per event, a trigger may fire on several
+	string_agg(t.event_manipulation::text, ' OR ' order by t.event_manipulation) as event_manipulation,
+	t.action_statement::text as action_statement
+from
+	information_schema.triggers t
+where
+	t.trigger_schema = $1
+group by
+	t.event_object_table,
+	t.trigger_name,
+	t.action_timing,
+	t.action_statement
+order by
+	event_object_table,
+	trigger_name
\ No newline at end of file
@@ -0,0 +1,26 @@
+select
+	v.table_name::text as table_name,
+	-- from the catalog, information_schema only lists columns the role has privileges on
+	(
+		select
+			string_agg(a.attname::text, ', ' order by a.attnum)
+		from
+			pg_catalog.pg_attribute a
+		where
+			a.attrelid = c.oid
+			and a.attnum > 0
+			and not a.attisdropped
+	) as view_columns,
+	-- information_schema only shows the definition to the owner
+	pg_get_viewdef(c.oid) as view_definition,
+	v.check_option::text as check_option,
+	v.is_updatable::text as is_updatable
+from
+	information_schema.views v
+	inner join pg_catalog.pg_namespace n on n.nspname = v.table_schema
+	inner join pg_catalog.pg_class c on c.relnamespace = n.oid
+		and c.relname = v.table_name
+where
+	v.table_schema = $1
+order by
+	v.table_name
\ No newline at end of file
@@ -0,0 +1,26 @@
+-- SQLite 查詢欄位資料
+select
+    -- 表格名稱
+    m.name as TABLE_NAME,
+    -- 欄位名稱
+    p.name as COLUMN_NAME,
+    -- 欄位排序
+    p.cid + 1 as ORDINAL_POSITION,
+    -- 欄位預設值
+    p.dflt_value as COLUMN_DEFAULT,
+    -- 允許空值
+    case p."notnull"
+        when 0 then
+            'YES'
+        else
+            'NO'
+        end IS_NULLABLE,
+    -- 欄位型別
+    p.type as COLUMN_TYPE,
+    -- 主鍵
+    p.pk as PRIMARY_KEY
+from sqlite_master m,
+     pragma_table_info(m.name) p
+where m.type = 'table'
+  and m.name not like 'sqlite_%'
+order by m.name, p.cid
\ No newline at end of file
@@ -0,0 +1,19 @@
+select
+	m.name as TABLE_NAME,
+	fk.id as CONSTRAINT_ID,
+	fk.seq + 1 as ORDINAL_POSITION,
+	fk."from" as COLUMN_NAME,
+	fk."table" as REFERENCED_TABLE_NAME,
+	coalesce(fk."to", '') as REFERENCED_COLUMN_NAME,
+	fk.on_update as UPDATE_RULE,
+	fk.on_delete as DELETE_RULE
+from
+	sqlite_master m,
+	pragma_foreign_key_list(m.name) fk
+where
+	m.type = 'table'
+	and m.name not like 'sqlite_%'
+order by
+	m.name,
+	fk.id,
+	fk.seq
\ No newline at end of file
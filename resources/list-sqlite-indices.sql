@@ -0,0 +1,28 @@
+select
+	m.name as TABLE_NAME,
+	il.name as INDEX_NAME,
+	il."unique" as IS_UNIQUE,
+	ii.seqno + 1 as SEQ_IN_INDEX,
+	coalesce(ii.name, '<expression>') as COLUMN_NAME
+from
+	sqlite_master m,
+	pragma_index_list(m.name) il,
+	pragma_index_info(il.name) ii
+where
+	m.type = 'table'
+	and m.name not like 'sqlite_%'
+	and il.origin <> 'pk'
+union all
+select
+	m.name as TABLE_NAME,
+	'PRIMARY' as INDEX_NAME,
+	1 as IS_UNIQUE,
+	p.pk as SEQ_IN_INDEX,
+	p.name as COLUMN_NAME
+from
+	sqlite_master m,
+	pragma_table_info(m.name) p
+where
+	m.type = 'table'
+	and m.name not like 'sqlite_%'
+	and p.pk > 0
\ No newline at end of file
@@ -0,0 +1,11 @@
+select
+	name as TABLE_NAME,
+	case
+		when upper(sql) like '%WITHOUT ROWID%' then 'WITHOUT ROWID'
+		end as ROW_FORMAT,
+	upper(sql) like '%AUTOINCREMENT%' as AUTO_INCREMENT
+from
+	sqlite_master
+where
+	type = 'table'
+	and name not like 'sqlite_%'
\ No newline at end of file
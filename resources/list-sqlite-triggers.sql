@@ -0,0 +1,10 @@
+select
+	name as TRIGGER_NAME,
+	tbl_name as TABLE_NAME,
+	sql
+from
+	sqlite_master
+where
+	type = 'trigger'
+order by
+	name
\ No newline at end of file
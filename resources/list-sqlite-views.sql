@@ -0,0 +1,15 @@
+select
+	m.name as TABLE_NAME,
+	(
+		select
+			group_concat(p.name, ', ')
+		from
+			(select name from pragma_table_info(m.name) order by cid) p
+	) as VIEW_COLUMNS,
+	m.sql as VIEW_DEFINITION
+from
+	sqlite_master m
+where
+	m.type = 'view'
+order by
+	m.name
\ No newline at end of file
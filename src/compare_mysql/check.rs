@@ -0,0 +1,80 @@
+use std::collections::{BTreeMap, BTreeSet};
+
+use super::diff::{column_rows, is_missing};
+use crate::value_object::{
+    ColumnInfo, ColumnRules, Deploy, DiffRow, Drift, DriftCategory, ObjectKind,
+};
+
+/// Count differing objects by category.
+///
+/// An object counts once in each category it falls in, e.g. a column missing from one deploy and
+/// changed in another. Columns which are identical once comments are ignored count as `Comment`,
+/// which needs comments to be compared by the rules. Partitions of missing tables count as
+/// `MissingTable`.
+pub fn find_drift(
+    deploys: &[Deploy],
+    rows: &[DiffRow],
+    columns: &[ColumnInfo],
+    rules: &ColumnRules,
+) -> Drift {
+    // columns compared without comments, cells which then match only differ in comment
+    let without_comment = ColumnRules {
+        comment: false,
+        ..rules.clone()
+    };
+    // only looked up for rows already found, columns of missing tables needn't be left out
+    let uncommented: BTreeMap<(String, String, String), DiffRow> =
+        column_rows(columns, &[], deploys, &without_comment)
+            .filter(|r| r.differs())
+            .map(|r| ((r.schema.clone(), r.table.clone(), r.name.clone()), r))
+            .collect();
+
+    let mut drift = Drift {
+        unreachable: deploys.iter().filter(|d| d.unreachable).count(),
+        ..Default::default()
+    };
+    for row in rows.iter().filter(|r| r.differs()) {
+        let uncommented = match row.kind {
+            ObjectKind::Column if rules.comment => {
+                uncommented.get(&(row.schema.clone(), row.table.clone(), row.name.clone()))
+            }
+            _ => None,
+        };
+        let categories: BTreeSet<DriftCategory> = row
+            .cells
+            .iter()
+            .enumerate()
+            .filter(|(_, c)| c.differs)
+            .map(|(index, cell)| {
+                let missing = is_missing(cell);
+                let comment_only =
+                    rules.comment && uncommented.map(|r| !r.cells[index].differs).unwrap_or(true);
+                match row.kind {
+                    ObjectKind::Table if missing => DriftCategory::MissingTable,
+                    ObjectKind::Table => DriftCategory::Table,
+                    ObjectKind::Column if missing => DriftCategory::MissingColumn,
+                    ObjectKind::Column if comment_only => DriftCategory::Comment,
+                    ObjectKind::Column => DriftCategory::Column,
+                    ObjectKind::Index if missing => DriftCategory::MissingIndex,
+                    ObjectKind::Index => DriftCategory::Index,
+                    ObjectKind::ForeignKey if missing => DriftCategory::MissingForeignKey,
+                    ObjectKind::ForeignKey => DriftCategory::ForeignKey,
+                    ObjectKind::Partition if missing => DriftCategory::MissingTable,
+                    ObjectKind::Partition => DriftCategory::Partition,
+                    ObjectKind::Trigger if missing => DriftCategory::MissingTrigger,
+                    ObjectKind::Trigger => DriftCategory::Trigger,
+                    ObjectKind::View if missing => DriftCategory::MissingView,
+                    ObjectKind::View => DriftCategory::View,
+                    ObjectKind::Routine if missing => DriftCategory::MissingRoutine,
+                    ObjectKind::Routine => DriftCategory::Routine,
+                    ObjectKind::Event if missing => DriftCategory::MissingEvent,
+                    ObjectKind::Event => DriftCategory::Event,
+                }
+            })
+            .collect();
+        for category in categories {
+            *drift.counts.entry(category).or_default() += 1;
+        }
+    }
+    drift
+}
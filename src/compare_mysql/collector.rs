@@ -1,181 +1,589 @@
-use log::{debug, info, trace};
-use mysql::{params, prelude::Queryable, Opts, Pool, PooledConn};
+use log::{debug, info, trace, warn};
 use std::{
     collections::{BTreeMap, HashMap},
-    fs::{self, OpenOptions},
-    include_str,
-    io::{BufWriter, Write},
+    fs::{self, File},
+    io::BufWriter,
     path::Path,
-    time::Instant,
-    vec,
+    result,
+    sync::{
+        atomic::{AtomicUsize, Ordering},
+        Mutex,
+    },
+    thread,
+    time::{Duration, Instant},
 };
 
+use super::{
+    check::find_drift,
+    diff::{
+        column_rows, event_rows, foreign_key_rows, index_rows, partition_rows, routine_rows,
+        table_rows, trigger_rows, view_rows,
+    },
+    engine::connect,
+    event::output_event_info,
+    migration::output_migration,
+    partition::output_partition_info,
+    report::{has_reports, save_diff, write_reports},
+    routine::{output_routine_diff, output_routine_info},
+    snapshot::save_snapshot,
+    trigger::output_trigger_info,
+    view::output_view_info,
+};
 use crate::value_object::{
-    ColumnInfo, ConnectInfo, Deploy, IndexInfo, Result, UniqueColumn, UniqueIndex,
+    CollectFailure, CollectOptions, ColumnInfo, ColumnRules, CompareRules, ConnectInfo, Deploy,
+    DiffRow, Drift, ForeignKeyInfo, IndexInfo, ObjectKind, OutputOptions, Result, Snapshot,
+    TableInfo, UniqueColumn, UniqueForeignKey, UniqueIndex, UniqueTable,
 };
 
+/// Cell value of deploys whose info couldn't be collected
+pub const UNREACHABLE: &str = "UNREACHABLE";
+/// Cell value of tables which don't exist in a deploy
+pub const MISSING: &str = "MISSING";
+/// Longest delay between two collection attempts of a database
+const MAX_RETRY_DELAY: Duration = Duration::from_secs(60);
+
 pub fn start(
-    configs: &Vec<ConnectInfo>,
-    source: bool,
-    columns_output_file: &str,
-    indice_output_file: &str,
-) -> Result<()> {
-    let mut columns: Vec<ColumnInfo> = vec![];
-    let mut indices: Vec<IndexInfo> = vec![];
+    configs: &[ConnectInfo],
+    collect_options: &CollectOptions,
+    options: &OutputOptions,
+) -> Result<Option<Drift>> {
+    let snapshot = collect(configs, collect_options)?;
+
+    if let Some(dir) = &options.snapshot {
+        save_snapshot(dir, &snapshot)?;
+    }
+
+    compare(&snapshot, options)
+}
 
+/// Collect schema info from all configured databases, up to `options.jobs` at the same time.
+///
+/// Collected info keeps the order of `configs`, whichever database responds first.
+pub fn collect(configs: &[ConnectInfo], options: &CollectOptions) -> Result<Snapshot> {
     let count = configs.len();
-    let mut iter = 0;
+    let jobs = options.jobs.clamp(1, count.max(1));
+    debug!("Collect info from {} databases with {} jobs", count, jobs);
 
-    for config in configs {
-        let time = Instant::now();
-        iter += 1;
-        info!(
-            "Collect info from {}/{} [mysql://{}:{}/{}]",
-            iter, count, config.host, config.port, config.schema
-        );
+    let next = AtomicUsize::new(0);
+    let finished = AtomicUsize::new(0);
+    // errors are kept as text, boxed errors can't be sent across threads
+    let results: Mutex<Vec<Option<result::Result<Snapshot, String>>>> =
+        Mutex::new((0..count).map(|_| None).collect());
 
-        // create database connection
-        let connect_time = Instant::now();
-        let url = format!(
-            "mysql://{}:{}@{}:{}/{}",
-            config.user, config.password, config.host, config.port, config.schema
-        );
-        let pool = Pool::new(Opts::from_url(&url)?)?;
-        let mut conn = pool.get_conn()?;
-        debug!(
-            "Prepare connection elapsed {}s",
-            connect_time.elapsed().as_secs()
+    thread::scope(|scope| {
+        for _ in 0..jobs {
+            scope.spawn(|| loop {
+                let iter = next.fetch_add(1, Ordering::SeqCst);
+                if iter >= count {
+                    break;
+                }
+                let config = &configs[iter];
+
+                let time = Instant::now();
+                info!(
+                    "Collect info from {}/{} [{}]",
+                    iter + 1,
+                    count,
+                    config.display_url()
+                );
+
+                let result = collect_source(config, options).map_err(|e| e.to_string());
+
+                debug!(
+                    "Collected {}/{} [{}] elapsed {}s",
+                    finished.fetch_add(1, Ordering::SeqCst) + 1,
+                    count,
+                    config.display_url(),
+                    time.elapsed().as_secs()
+                );
+                results.lock().unwrap()[iter] = Some(result);
+            });
+        }
+    });
+
+    let mut snapshot = Snapshot {
+        sources: configs.to_vec(),
+        ..Default::default()
+    };
+    for (config, result) in configs.iter().zip(results.into_inner().unwrap()) {
+        match result.unwrap() {
+            Ok(mut source) => {
+                snapshot.tables.append(&mut source.tables);
+                snapshot.columns.append(&mut source.columns);
+                snapshot.indices.append(&mut source.indices);
+                snapshot.foreign_keys.append(&mut source.foreign_keys);
+                snapshot.triggers.append(&mut source.triggers);
+                snapshot.views.append(&mut source.views);
+                snapshot.routines.append(&mut source.routines);
+                snapshot.events.append(&mut source.events);
+                snapshot.partitions.append(&mut source.partitions);
+            }
+            Err(e) if options.continue_on_error => snapshot.failures.push(CollectFailure {
+                country: config.country.clone(),
+                environment: config.environment.clone(),
+                source: config.display_url(),
+                error: e,
+            }),
+            Err(e) => {
+                return Err(format!(
+                    "Failed to collect info from [{}]: {}",
+                    config.display_url(),
+                    e
+                )
+                .into())
+            }
+        }
+    }
+
+    if !snapshot.failures.is_empty() {
+        warn!(
+            "Failed to collect info from {}/{} databases",
+            snapshot.failures.len(),
+            count
         );
+        for failure in &snapshot.failures {
+            warn!(
+                "  [{} {}] {}: {}",
+                failure.environment, failure.country, failure.source, failure.error
+            );
+        }
+    }
+
+    Ok(snapshot)
+}
+
+/// Collect schema info from one database, retrying with a doubled delay after each failure, up to
+/// [`MAX_RETRY_DELAY`]
+fn collect_source(config: &ConnectInfo, options: &CollectOptions) -> Result<Snapshot> {
+    let mut delay = options.retry_delay;
+    let mut attempt = 0;
+    loop {
+        match try_collect_source(config, options) {
+            Err(e) if attempt < options.retries => {
+                attempt += 1;
+                warn!(
+                    "Failed to collect info from [{}], retry {}/{} in {}ms: {}",
+                    config.display_url(),
+                    attempt,
+                    options.retries,
+                    delay.as_millis(),
+                    e
+                );
+                thread::sleep(delay);
+                delay = delay
+                    .checked_mul(2)
+                    .unwrap_or(MAX_RETRY_DELAY)
+                    .min(MAX_RETRY_DELAY);
+            }
+            result => return result,
+        }
+    }
+}
 
+fn try_collect_source(config: &ConnectInfo, options: &CollectOptions) -> Result<Snapshot> {
+    // create database connection
+    let connect_time = Instant::now();
+    let mut collector = connect(config, options.timeout)?;
+    debug!(
+        "Prepare connection of [{}] elapsed {}s",
+        config.display_url(),
+        connect_time.elapsed().as_secs()
+    );
+
+    Ok(Snapshot {
+        sources: vec![config.clone()],
+        // collect table data
+        tables: collector.collect_tables()?,
         // collect column data
-        let mut set_of_columns = collect_column_info(&config, &mut conn)?;
-        columns.append(&mut set_of_columns);
+        columns: collector.collect_columns()?,
         // collect index data
-        let mut set_of_indices = collect_index_info(&config, &mut conn)?;
-        indices.append(&mut set_of_indices);
-
-        debug!(
-            "Collected from {}/{} [mysql://{}:{}/{}] elapsed {}s",
-            iter,
-            count,
-            config.host,
-            config.port,
-            config.schema,
-            time.elapsed().as_secs()
-        );
+        indices: collector.collect_indices()?,
+        // collect foreign key data
+        foreign_keys: collector.collect_foreign_keys()?,
+        // collect trigger data
+        triggers: collector.collect_triggers()?,
+        // collect views and their columns
+        views: collector.collect_views()?,
+        // collect stored procedures and functions
+        routines: collector.collect_routines()?,
+        // collect scheduled events
+        events: collector.collect_events()?,
+        // collect partitions of partitioned tables
+        partitions: collector.collect_partitions()?,
+        failures: vec![],
+    })
+}
+
+/// Write comparison outputs of collected schema info, and return the differences found with
+/// `options.check`.
+///
+/// CSV outputs are written row by row, all rows are only kept in memory when the comparison is
+/// saved, rendered as a report or checked.
+pub fn compare(snapshot: &Snapshot, options: &OutputOptions) -> Result<Option<Drift>> {
+    let mut deploys = build_deploy(&snapshot.sources)?;
+    for deploy in deploys.iter_mut() {
+        deploy.unreachable = snapshot
+            .failures
+            .iter()
+            .any(|f| f.environment == deploy.environment && f.country == deploy.country);
     }
 
-    if source {
-        save_source(&columns, &indices)?;
+    if let Some(baseline) = &options.baseline {
+        set_baseline(&mut deploys, baseline)?;
+    }
+    if let Some(deploy) = deploys.iter().find(|d| d.baseline) {
+        if deploy.unreachable {
+            warn!(
+                "Baseline [{} {}] is unreachable, comparing with the most used definition instead",
+                deploy.environment, deploy.country
+            );
+        } else {
+            info!(
+                "Comparing with baseline [{} {}]",
+                deploy.environment, deploy.country
+            );
+        }
     }
 
-    let deploys = build_deploy(&configs)?;
+    if let Some(file) = &options.tables {
+        output_table_info(
+            file,
+            &snapshot.tables,
+            &deploys,
+            options.delimiter,
+            options.only_diff,
+        )?;
+    }
+    if let Some(file) = &options.missing_tables {
+        output_missing_tables(file, &snapshot.tables, &deploys, options.delimiter)?;
+    }
 
-    output_column_info(columns_output_file, &mut columns, &deploys)?;
-    output_index_info(indice_output_file, &mut indices, &deploys)?;
+    let stripped_columns;
+    let columns = if options.ignore_default_collation {
+        stripped_columns = strip_default_collation(&snapshot.columns, &snapshot.tables);
+        &stripped_columns
+    } else {
+        &snapshot.columns
+    };
 
-    Ok(())
+    if let Some(file) = &options.columns {
+        output_column_info(
+            file,
+            columns,
+            &snapshot.tables,
+            &deploys,
+            &options.rules.columns,
+            options.delimiter,
+            options.only_diff,
+        )?;
+    }
+    if let Some(file) = &options.indices {
+        output_index_info(
+            file,
+            &snapshot.indices,
+            &snapshot.tables,
+            &deploys,
+            options.delimiter,
+            options.only_diff,
+        )?;
+    }
+    if let Some(file) = &options.foreign_keys {
+        output_foreign_key_info(
+            file,
+            &snapshot.foreign_keys,
+            &snapshot.tables,
+            &deploys,
+            options.delimiter,
+            options.only_diff,
+        )?;
+    }
+    if let Some(file) = &options.partitions {
+        output_partition_info(
+            file,
+            &snapshot.partitions,
+            &snapshot.tables,
+            &deploys,
+            options.ignore_partition_boundaries,
+            options.delimiter,
+            options.only_diff,
+        )?;
+    }
+    if let Some(file) = &options.triggers {
+        output_trigger_info(
+            file,
+            &snapshot.triggers,
+            &deploys,
+            options.delimiter,
+            options.only_diff,
+        )?;
+    }
+    if let Some(file) = &options.views {
+        output_view_info(
+            file,
+            &snapshot.views,
+            &deploys,
+            options.delimiter,
+            options.only_diff,
+        )?;
+    }
+    if let Some(file) = &options.routines {
+        output_routine_info(
+            file,
+            &snapshot.routines,
+            &deploys,
+            options.delimiter,
+            options.only_diff,
+        )?;
+    }
+    if let Some(file) = &options.routine_diff {
+        output_routine_diff(file, &snapshot.routines, &deploys)?;
+    }
+    if let Some(file) = &options.events {
+        output_event_info(
+            file,
+            &snapshot.events,
+            &deploys,
+            options.delimiter,
+            options.only_diff,
+        )?;
+    }
+
+    if let Some(dir) = &options.migration {
+        output_migration(
+            dir,
+            columns,
+            &snapshot.indices,
+            &snapshot.foreign_keys,
+            &deploys,
+            &options.rules.columns,
+        )?;
+    }
+
+    if options.diff.is_none() && !has_reports(options) && !options.check {
+        return Ok(Option::None);
+    }
+
+    let rows: Vec<DiffRow> = table_rows(&snapshot.tables, &deploys)
+        .chain(column_rows(
+            columns,
+            &snapshot.tables,
+            &deploys,
+            &options.rules.columns,
+        ))
+        .chain(index_rows(&snapshot.indices, &snapshot.tables, &deploys))
+        .chain(foreign_key_rows(
+            &snapshot.foreign_keys,
+            &snapshot.tables,
+            &deploys,
+        ))
+        .chain(partition_rows(
+            &snapshot.partitions,
+            &snapshot.tables,
+            &deploys,
+            options.ignore_partition_boundaries,
+        ))
+        .chain(trigger_rows(&snapshot.triggers, &deploys))
+        .chain(view_rows(&snapshot.views, &deploys))
+        .chain(routine_rows(&snapshot.routines, &deploys))
+        .chain(event_rows(&snapshot.events, &deploys))
+        .collect();
+    if let Some(file) = &options.diff {
+        save_diff(file, &deploys, &rows)?;
+    }
+    write_reports(&deploys, &rows, options)?;
+
+    Ok(options
+        .check
+        .then(|| find_drift(&deploys, &rows, columns, &options.rules.columns)))
 }
 
-pub fn build_deploy(configs: &Vec<ConnectInfo>) -> Result<Vec<Deploy>> {
+pub fn build_deploy(configs: &[ConnectInfo]) -> Result<Vec<Deploy<'_>>> {
     let mut deploys: Vec<Deploy> = configs.iter().map(|c| c.to_deploy()).collect();
-    deploys.dedup(); // make unique
     deploys.sort_by(|a, b| (a.environment, a.country).cmp(&(b.environment, b.country)));
+    // make unique, a deploy is the baseline when any of its configs is
+    deploys.dedup_by(|a, b| {
+        let same = a.environment == b.environment && a.country == b.country;
+        if same {
+            b.baseline |= a.baseline;
+        }
+        same
+    });
+
+    if deploys.iter().filter(|d| d.baseline).count() > 1 {
+        return Err("More than one deploy is configured as baseline".into());
+    }
     Ok(deploys)
 }
 
-fn save_source(columns: &Vec<ColumnInfo>, indices: &Vec<IndexInfo>) -> Result<()> {
-    let file = OpenOptions::new()
-        .write(true)
-        .truncate(true)
-        .create(true)
-        .open("columns.json")?;
+/// Make the deploy named `<environment>:<country>` the baseline instead of the configured one
+pub fn set_baseline(deploys: &mut [Deploy], baseline: &str) -> Result<()> {
+    let (environment, country) = baseline.split_once(':').ok_or_else(|| {
+        format!(
+            "Invalid baseline {}, expected <environment>:<country>",
+            baseline
+        )
+    })?;
+    if !deploys
+        .iter()
+        .any(|d| d.environment == environment && d.country == country)
+    {
+        return Err(format!(
+            "Baseline [{} {}] is not a configured deploy",
+            environment, country
+        )
+        .into());
+    }
 
-    let writer = BufWriter::new(file);
-    serde_json::to_writer(writer, columns)?;
+    for deploy in deploys.iter_mut() {
+        deploy.baseline = deploy.environment == environment && deploy.country == country;
+    }
+    Ok(())
+}
 
-    let file = OpenOptions::new()
-        .write(true)
-        .truncate(true)
-        .create(true)
-        .open("indices.json")?;
+pub fn output_table_info(
+    file: &str,
+    tables: &[TableInfo],
+    deploys: &[Deploy],
+    delimiter: u8,
+    only_diff: bool,
+) -> Result<()> {
+    debug!("Generating csv for {} tables", tables.len());
+    write_csv(
+        file,
+        delimiter,
+        only_diff,
+        ObjectKind::Table,
+        deploys,
+        table_rows(tables, deploys),
+    )
+}
 
-    let writer = BufWriter::new(file);
-    serde_json::to_writer(writer, indices)?;
-    Ok(())
+/// Report tables which exist in some deploys but not in others
+pub fn output_missing_tables(
+    file: &str,
+    tables: &[TableInfo],
+    deploys: &[Deploy],
+    delimiter: u8,
+) -> Result<()> {
+    let rows: Vec<DiffRow> = table_rows(tables, deploys).collect();
+    write_missing_tables(file, deploys, &rows, delimiter)
 }
 
-fn collect_column_info(config: &ConnectInfo, conn: &mut PooledConn) -> Result<Vec<ColumnInfo>> {
-    let query = include_str!("../../resources/list-mysql-columns.sql");
+/// Write the tables missing in some reachable deploys, from the `MISSING` cells of table rows
+pub(crate) fn write_missing_tables(
+    file: &str,
+    deploys: &[Deploy],
+    rows: &[DiffRow],
+    delimiter: u8,
+) -> Result<()> {
+    let mut writer = csv_writer(file, delimiter)?;
+    writer.write_record(["Schema", "Table", "Missing", "Existing"])?;
 
-    let stmt = conn.prep(&query)?;
-    let result = conn.exec_map(
-        stmt,
-        params! { "schema" => config.schema.clone() },
-        |mut row: mysql::Row| {
-            trace!("{:?}", row);
+    let mut count = 0;
+    for row in rows.iter().filter(|r| r.kind == ObjectKind::Table) {
+        let names = |missing: bool| {
+            deploys
+                .iter()
+                .zip(&row.cells)
+                .filter(|(_, cell)| !cell.unreachable && (cell.value == MISSING) == missing)
+                .map(|(d, _)| format!("[{} {}]", d.environment, d.country))
+                .collect::<Vec<String>>()
+                .join(", ")
+        };
+        let missing = names(true);
+        if missing.is_empty() {
+            continue;
+        }
 
-            ColumnInfo {
-                country: config.country.clone(),
-                environment: config.environment.clone(),
-                schema: config.schema.clone(),
-
-                table: row.take("TABLE_NAME").unwrap(),
-                column_name: row.take("COLUMN_NAME").unwrap(),
-                column_type: row.take("COLUMN_TYPE").unwrap(),
-                data_type: row.take("DATA_TYPE").unwrap(),
-                text_max_length: row.get_opt("CHARACTER_MAXIMUM_LENGTH").unwrap().unwrap_or(Option::None),
-                text_octet_length: row.get_opt("CHARACTER_OCTET_LENGTH").unwrap().unwrap_or(Option::None),
-                num_precision: row.get_opt("NUMERIC_PRECISION").unwrap().unwrap_or(Option::None),
-                default_value: row.get_opt("COLUMN_DEFAULT").unwrap().unwrap_or(Option::None),
-                nullable: row.take("IS_NULLABLE").unwrap(),
-                num_scale: row.get_opt("NUMERIC_SCALE").unwrap().unwrap_or(Option::None),
-                comment: row.get_opt("COLUMN_COMMENT").unwrap().unwrap_or(Option::None),
-
-                display: Option::None,
-            }
-        },
-    )?;
+        count += 1;
+        writer.write_record([
+            row.schema.as_str(),
+            row.table.as_str(),
+            missing.as_str(),
+            names(false).as_str(),
+        ])?;
+    }
+    info!("{} tables are missing in some deploys", count);
 
-    Ok(result)
+    writer.flush()?;
+    Ok(())
 }
 
-fn collect_index_info(config: &ConnectInfo, conn: &mut PooledConn) -> Result<Vec<IndexInfo>> {
-    let query = include_str!("../../resources/list-mysql-indices.sql");
+pub(crate) fn group_tables(tables: &[TableInfo]) -> BTreeMap<UniqueTable<'_>, Vec<&TableInfo>> {
+    tables.iter().fold(
+        BTreeMap::new(),
+        |mut acc: BTreeMap<UniqueTable, Vec<&TableInfo>>, t| {
+            acc.entry(UniqueTable {
+                schema: &t.schema,
+                table: &t.table,
+            })
+            .or_default()
+            .push(t);
+            acc
+        },
+    )
+}
 
-    let stmt = conn.prep(&query)?;
-    let result = conn.exec_map(
-        stmt,
-        params! { "schema" => config.schema.clone() },
-        |mut row: mysql::Row| {
-            trace!("{:?}", row);
-            IndexInfo {
-                country: config.country.clone(),
-                environment: config.environment.clone(),
-                schema: config.schema.clone(),
+pub(crate) fn find_table<'a>(list: &[&'a TableInfo], deploy: &Deploy) -> Option<&'a TableInfo> {
+    list.iter()
+        .find(|t| t.environment == deploy.environment && t.country == deploy.country)
+        .copied()
+}
 
-                table: row.take("TABLE_NAME").unwrap(),
-                index_name: row.take("INDEX_NAME").unwrap(),
-                unique: row.take("NON_UNIQUE").unwrap(),
-                sequence: row.take("SEQ_IN_INDEX").unwrap(),
-                column: row.take("COLUMN_NAME").unwrap(),
+pub(crate) fn table_format(item: &TableInfo) -> String {
+    let mut values = vec![];
+    if let Some(engine) = &item.engine {
+        values.push(format!("ENGINE={}", engine));
+    }
+    if let Some(collation) = &item.collation {
+        values.push(format!("COLLATE={}", collation));
+    }
+    if let Some(row_format) = &item.row_format {
+        values.push(format!("ROW_FORMAT={}", row_format));
+    }
+    if item.auto_increment {
+        values.push("AUTO_INCREMENT".to_string());
+    }
+    if let Some(comment) = item.comment.as_ref().filter(|c| !c.is_empty()) {
+        values.push(format!("COMMENT='{}'", comment));
+    }
+    values.join(" ")
+}
 
-                display: Option::None,
-            }
-        },
-    )?;
+/// Missing tables take part in the vote like any other definition, as the table itself is compared
+pub(crate) fn find_most_used_table(deploys: &[Deploy], list: &[&TableInfo]) -> String {
+    let values = deploys
+        .iter()
+        .map(|deploy| find_table(list, deploy).map(table_format))
+        .collect();
 
-    Ok(result)
+    select_reference(deploys, values).unwrap_or_else(|| MISSING.to_string())
 }
 
 pub fn output_column_info(
     file: &str,
-    columns: &Vec<ColumnInfo>,
-    deploys: &Vec<Deploy>,
+    columns: &[ColumnInfo],
+    tables: &[TableInfo],
+    deploys: &[Deploy],
+    rules: &ColumnRules,
+    delimiter: u8,
+    only_diff: bool,
 ) -> Result<()> {
     debug!("Generating csv for {} columns", columns.len());
+    write_csv(
+        file,
+        delimiter,
+        only_diff,
+        ObjectKind::Column,
+        deploys,
+        column_rows(columns, tables, deploys, rules),
+    )
+}
 
-    let groups = columns.into_iter().fold(
+pub(crate) fn group_columns(
+    columns: &[ColumnInfo],
+) -> BTreeMap<UniqueColumn<'_>, Vec<&ColumnInfo>> {
+    columns.iter().fold(
         BTreeMap::new(),
         |mut acc: BTreeMap<UniqueColumn, Vec<&ColumnInfo>>, c| {
             acc.entry(UniqueColumn {
@@ -187,116 +595,209 @@ pub fn output_column_info(
             .push(c);
             acc
         },
-    );
-
-    let mut output = String::new();
-
-    // header line
-    output += "Schema\tTable\tColumn";
-    for deploy in deploys {
-        output += format!("\t[{} {}]", deploy.environment, deploy.country).as_str();
-    }
-    output += "\n";
-
-    for (key, list) in groups {
-        trace!("unique column: {:?}", key);
-        output += format!("{}\t{}\t{}", key.schema, key.table, key.column_name).as_str();
+    )
+}
 
-        let most_used = find_most_used_column(&list, deploys.len() as i16);
+/// Clear charset and collation of columns which use the default collation of their table, so
+/// deploys with different table defaults are only reported in the table comparison
+pub fn strip_default_collation(columns: &[ColumnInfo], tables: &[TableInfo]) -> Vec<ColumnInfo> {
+    // collation of each table by (environment, country, schema, table)
+    let collations: HashMap<(&str, &str, &str, &str), &String> = tables
+        .iter()
+        .filter_map(|t| {
+            t.collation.as_ref().map(|collation| {
+                (
+                    (
+                        t.environment.as_str(),
+                        t.country.as_str(),
+                        t.schema.as_str(),
+                        t.table.as_str(),
+                    ),
+                    collation,
+                )
+            })
+        })
+        .collect();
 
-        for deploy in deploys {
-            if let Some(item) = list
-                .iter()
-                .find(|c| c.environment == deploy.environment && c.country == deploy.country)
-            {
-                let format = column_format(item);
-                if most_used == format {
-                    output += "\t";
-                } else {
-                    output += "\t**";
-                }
-                output += format.as_str();
-            } else {
-                if most_used.is_empty() {
-                    output += "\t";
-                } else {
-                    output += "\t**";
-                }
+    columns
+        .iter()
+        .map(|column| {
+            let mut column = column.clone();
+            let table_collation = collations
+                .get(&(
+                    column.environment.as_str(),
+                    column.country.as_str(),
+                    column.schema.as_str(),
+                    column.table.as_str(),
+                ))
+                .copied();
+            if column.collation.is_none() || column.collation.as_ref() == table_collation {
+                column.charset = Option::None;
+                column.collation = Option::None;
             }
+            column
+        })
+        .collect()
+}
+
+/// Compared definition of a column, only with the attributes enabled in `rules`
+pub(crate) fn column_format(item: &ColumnInfo, rules: &ColumnRules) -> String {
+    let mut parts: Vec<String> = vec![];
+    if rules.column_type {
+        parts.push(if rules.ignore_integer_width {
+            strip_integer_width(&item.column_type)
+        } else {
+            item.column_type.clone()
+        });
+    }
+    if rules.charset {
+        if let Some(charset) = &item.charset {
+            parts.push(format!("CHARACTER SET {}", charset));
+        }
+        if let Some(collation) = &item.collation {
+            parts.push(format!("COLLATE {}", collation));
         }
-        output += "\n";
     }
-
-    if !Path::new(file).parent().unwrap().exists() {
-        fs::create_dir_all(Path::new(file).parent().unwrap())?;
+    if rules.nullable && item.nullable == "YES" {
+        parts.push("NULL".to_string());
+    }
+    if rules.default_value {
+        if let Some(value) = item
+            .default_value
+            .as_ref()
+            .filter(|v| !(rules.null_default_as_none && v.eq_ignore_ascii_case("NULL")))
+        {
+            parts.push(value.clone());
+        }
+    }
+    if rules.extra {
+        // MySQL 8 marks columns with an expression default, which is compared as the default
+        if let Some(extra) = &item.extra {
+            parts.push(extra.replace("DEFAULT_GENERATED", ""));
+        }
+    }
+    if rules.primary_key && item.primary_key {
+        parts.push("PRIMARY KEY".to_string());
+    }
+    if rules.comment {
+        if let Some(comment) = item.comment.as_ref().filter(|c| !c.is_empty()) {
+            parts.push(format!("COMMENT '{}'", comment));
+        }
+    }
+    if rules.ordinal_position {
+        parts.push(format!("POSITION {}", item.ordinal_position));
     }
 
-    let file = OpenOptions::new()
-        .write(true)
-        .truncate(true)
-        .create(true)
-        .open(file)?;
-
-    let mut writer = BufWriter::new(file);
-    writer.write_all(output.as_bytes())?;
-
-    Ok(())
+    parts
+        .iter()
+        .map(|p| p.trim())
+        .filter(|p| !p.is_empty())
+        .collect::<Vec<&str>>()
+        .join(" ")
 }
 
-fn column_format(item: &ColumnInfo) -> String {
-    format!(
-        "{}{} {}",
-        item.column_type,
-        if item.nullable == "YES" { " NULL" } else { "" },
-        item.default_value.as_ref().unwrap_or(&String::new())
-    )
-    .trim()
-    .to_string()
+/// Remove the display width of integer types, e.g. `int(11) unsigned` to `int unsigned`
+fn strip_integer_width(column_type: &str) -> String {
+    let integer_types = [
+        "tinyint",
+        "smallint",
+        "mediumint",
+        "int",
+        "integer",
+        "bigint",
+    ];
+    match column_type.split_once('(') {
+        Some((name, rest)) if integer_types.contains(&name.trim().to_lowercase().as_str()) => {
+            match rest.split_once(')') {
+                Some((_, suffix)) => format!("{}{}", name, suffix),
+                None => column_type.to_string(),
+            }
+        }
+        _ => column_type.to_string(),
+    }
 }
 
-fn find_most_used_column(list: &Vec<&ColumnInfo>, deploy_count: i16) -> String {
-    let counted_map = list.into_iter().map(|item| column_format(item)).fold(
-        HashMap::new(),
-        |mut acc: HashMap<String, i16>, item| {
-            let counter = acc.entry(item).or_insert(0);
-            *counter += 1;
-            acc
-        },
-    );
+pub(crate) fn find_most_used_column(
+    deploys: &[Deploy],
+    list: &[&ColumnInfo],
+    rules: &ColumnRules,
+) -> String {
+    let values = deploys
+        .iter()
+        .map(|deploy| {
+            list.iter()
+                .find(|c| c.environment == deploy.environment && c.country == deploy.country)
+                .map(|c| column_format(c, rules))
+        })
+        .collect();
 
-    select_max_one(counted_map, list.len() as i16, deploy_count)
+    select_reference(deploys, values).unwrap_or_default()
 }
 
-fn select_max_one(
-    counted_map: HashMap<String, i16>,
-    value_count: i16,
-    deploy_count: i16,
-) -> String {
-    let result = &mut String::new();
-    let mut count = 0;
-    for (k, v) in counted_map {
-        if count < v {
-            *result = k;
-            count = v;
+/// Select the definition every deploy is compared with, out of `values` holding the definition
+/// of each deploy in `deploys` (`None` when the object doesn't exist in the deploy).
+///
+/// The definition of the baseline deploy is used when it is reachable. Otherwise the most used
+/// definition among reachable deploys is selected, and ties are broken deterministically: an
+/// existing definition wins over a missing object, then the definition of the first deploy
+/// (ordered by environment and country) wins.
+pub(crate) fn select_reference(deploys: &[Deploy], values: Vec<Option<String>>) -> Option<String> {
+    let candidates: Vec<(&Deploy, Option<String>)> = deploys
+        .iter()
+        .zip(values)
+        .filter(|(deploy, _)| !deploy.unreachable)
+        .collect();
+
+    if let Some((_, value)) = candidates.iter().find(|(deploy, _)| deploy.baseline) {
+        return value.clone();
+    }
+
+    // counts in order of the first deploy holding each definition
+    let mut counted: Vec<(Option<String>, i16)> = vec![];
+    for (_, value) in candidates {
+        match counted.iter_mut().find(|(v, _)| *v == value) {
+            Some((_, count)) => *count += 1,
+            None => counted.push((value, 1)),
         }
     }
 
-    // deploy_count - value_count = whitespace cell count
-    if deploy_count - value_count > count {
-        String::new()
-    } else {
-        result.to_string()
+    let mut selected: Option<(Option<String>, i16)> = Option::None;
+    for (value, count) in counted {
+        let better = match &selected {
+            Some((current, current_count)) => {
+                count > *current_count
+                    || (count == *current_count && current.is_none() && value.is_some())
+            }
+            None => true,
+        };
+        if better {
+            selected = Some((value, count));
+        }
     }
+    selected.and_then(|(value, _)| value)
 }
 
 pub fn output_index_info(
     file: &str,
-    indices: &Vec<IndexInfo>,
-    deploys: &Vec<Deploy>,
+    indices: &[IndexInfo],
+    tables: &[TableInfo],
+    deploys: &[Deploy],
+    delimiter: u8,
+    only_diff: bool,
 ) -> Result<()> {
     debug!("Generating csv for {} indices", indices.len());
+    write_csv(
+        file,
+        delimiter,
+        only_diff,
+        ObjectKind::Index,
+        deploys,
+        index_rows(indices, tables, deploys),
+    )
+}
 
-    let groups = indices.iter().fold(
+pub(crate) fn group_indices(indices: &[IndexInfo]) -> BTreeMap<UniqueIndex<'_>, Vec<&IndexInfo>> {
+    indices.iter().fold(
         BTreeMap::new(),
         |mut acc: BTreeMap<UniqueIndex, Vec<&IndexInfo>>, i| {
             acc.entry(UniqueIndex {
@@ -308,99 +809,239 @@ pub fn output_index_info(
             .push(i);
             acc
         },
-    );
-
-    let mut output = String::new();
-
-    // header line
-    output += "Schema\tTable\tIndex";
-    for deploy in deploys {
-        output += format!("\t[{} {}]", deploy.environment, deploy.country).as_str();
-    }
-    output += "\n";
+    )
+}
 
-    for (key, list) in groups {
-        trace!("unique index: {:?}", key);
-        output += format!("{}\t{}\t{}", key.schema, key.table, key.index_name).as_str();
+/// Columns of an index in the given deploy, ordered by sequence
+pub(crate) fn find_index_columns<'a>(
+    list: &[&'a IndexInfo],
+    deploy: &Deploy,
+) -> Vec<&'a IndexInfo> {
+    let mut columns: Vec<&IndexInfo> = list
+        .iter()
+        .filter(|i| i.environment == deploy.environment && i.country == deploy.country)
+        .copied()
+        .collect();
+    columns.sort_by_key(|c| c.sequence);
+    columns
+}
 
-        // find most used
-        let most_used = find_most_used_index(deploys, &list);
+pub(crate) fn index_format(columns: &[&IndexInfo]) -> String {
+    let unique = if columns[0].unique { "UNIQUE " } else { "" };
+    let values = columns
+        .iter()
+        .map(|c| c.column.as_str())
+        .collect::<Vec<&str>>()
+        .join(", ");
+    format!("{}{}", unique, values)
+}
 
-        for deploy in deploys {
-            let mut columns: Vec<&IndexInfo> = list
-                .iter()
-                .filter(|i| i.environment == deploy.environment && i.country == deploy.country)
-                .map(|i| *i)
-                .collect();
-
-            output += "\t";
-            if columns.len() > 0 {
-                columns.sort_by(|a, b| a.sequence.cmp(&b.sequence));
-                let format = index_format(&columns);
-                if format != most_used {
-                    output += "**";
-                }
-                output += format.as_str();
+pub(crate) fn find_most_used_index(deploys: &[Deploy], list: &[&IndexInfo]) -> String {
+    let values = deploys
+        .iter()
+        .map(|deploy| {
+            let columns = find_index_columns(list, deploy);
+            if columns.is_empty() {
+                Option::None
             } else {
-                if !most_used.is_empty() {
-                    output += "**";
-                }
+                Some(index_format(&columns))
             }
-        }
-        output += "\n";
-    }
-
-    if !Path::new(file).parent().unwrap().exists() {
-        fs::create_dir_all(Path::new(file).parent().unwrap())?;
-    }
+        })
+        .collect();
 
-    let file = OpenOptions::new()
-        .write(true)
-        .truncate(true)
-        .create(true)
-        .open(file)?;
+    select_reference(deploys, values).unwrap_or_default()
+}
 
-    let mut writer = BufWriter::new(file);
-    writer.write_all(output.as_bytes())?;
+pub fn output_foreign_key_info(
+    file: &str,
+    foreign_keys: &[ForeignKeyInfo],
+    tables: &[TableInfo],
+    deploys: &[Deploy],
+    delimiter: u8,
+    only_diff: bool,
+) -> Result<()> {
+    debug!("Generating csv for {} foreign keys", foreign_keys.len());
+    write_csv(
+        file,
+        delimiter,
+        only_diff,
+        ObjectKind::ForeignKey,
+        deploys,
+        foreign_key_rows(foreign_keys, tables, deploys),
+    )
+}
 
-    Ok(())
+pub(crate) fn group_foreign_keys(
+    foreign_keys: &[ForeignKeyInfo],
+) -> BTreeMap<UniqueForeignKey<'_>, Vec<&ForeignKeyInfo>> {
+    foreign_keys.iter().fold(
+        BTreeMap::new(),
+        |mut acc: BTreeMap<UniqueForeignKey, Vec<&ForeignKeyInfo>>, f| {
+            acc.entry(UniqueForeignKey {
+                schema: &f.schema,
+                table: &f.table,
+                constraint_name: &f.constraint_name,
+            })
+            .or_default()
+            .push(f);
+            acc
+        },
+    )
 }
 
-fn index_format(columns: &Vec<&IndexInfo>) -> String {
-    let unique = if columns[0].unique { "UNIQUE " } else { "" };
+/// Referenced tables are qualified with their schema when it isn't the schema of the constraint
+pub(crate) fn foreign_key_format(columns: &[&ForeignKeyInfo]) -> String {
+    let first = columns[0];
+    let referenced_table =
+        if first.referenced_schema.is_empty() || first.referenced_schema == first.schema {
+            first.referenced_table.clone()
+        } else {
+            format!("{}.{}", first.referenced_schema, first.referenced_table)
+        };
     let values = columns
         .iter()
         .map(|c| c.column.as_str())
         .collect::<Vec<&str>>()
         .join(", ");
-    format!("{}{}", unique, values)
+    let references = columns
+        .iter()
+        .map(|c| c.referenced_column.as_str())
+        .collect::<Vec<&str>>()
+        .join(", ");
+    format!(
+        "({}) REFERENCES {}({}) ON UPDATE {} ON DELETE {}",
+        values, referenced_table, references, first.update_rule, first.delete_rule
+    )
 }
 
-fn find_most_used_index(deploys: &Vec<Deploy>, list: &Vec<&IndexInfo>) -> String {
-    let counted_map = deploys
+/// Columns of a foreign key in the given deploy, ordered by their sequence
+pub(crate) fn find_foreign_key_columns<'a>(
+    list: &[&'a ForeignKeyInfo],
+    deploy: &Deploy,
+) -> Vec<&'a ForeignKeyInfo> {
+    let mut columns: Vec<&ForeignKeyInfo> = list
         .iter()
-        .map(|deploy| {
-            let mut columns: Vec<&IndexInfo> = list
-                .iter()
-                .filter(|i| i.environment == deploy.environment && i.country == deploy.country)
-                .map(|i| *i)
-                .collect();
+        .filter(|f| f.environment == deploy.environment && f.country == deploy.country)
+        .copied()
+        .collect();
+    columns.sort_by_key(|c| c.sequence);
+    columns
+}
 
-            if columns.len() == 0 {
-                String::new()
+pub(crate) fn find_most_used_foreign_key(deploys: &[Deploy], list: &[&ForeignKeyInfo]) -> String {
+    let values = deploys
+        .iter()
+        .map(|deploy| {
+            let columns = find_foreign_key_columns(list, deploy);
+            if columns.is_empty() {
+                Option::None
             } else {
-                columns.sort_by(|a, b| a.sequence.cmp(&b.sequence));
-                index_format(&columns)
+                Some(foreign_key_format(&columns))
             }
         })
-        .filter(|item| !item.is_empty())
-        .fold(HashMap::new(), |mut acc: HashMap<String, i16>, item| {
-            let value = acc.entry(item).or_insert(0);
-            *value += 1;
-            acc
-        });
+        .collect();
+
+    select_reference(deploys, values).unwrap_or_default()
+}
 
-    select_max_one(counted_map, list.len() as i16, deploys.len() as i16)
+/// Write rows of one kind of object, the key columns followed by one column per deploy.
+///
+/// Cells which differ from the reference definition are prefixed with `**`, and cells of
+/// unreachable deploys are [`UNREACHABLE`]. With `only_diff`, rows identical in all deploys are
+/// left out and their count is written in a footer row.
+pub(crate) fn write_csv(
+    file: &str,
+    delimiter: u8,
+    only_diff: bool,
+    kind: ObjectKind,
+    deploys: &[Deploy],
+    rows: impl Iterator<Item = DiffRow>,
+) -> Result<()> {
+    let mut writer = csv_writer(file, delimiter)?;
+
+    let mut header = vec!["Schema".to_string()];
+    if kind.in_table() {
+        header.push("Table".to_string());
+    }
+    if kind.named() {
+        header.push(kind.label().to_string());
+    }
+    if kind == ObjectKind::Routine {
+        header.push("Type".to_string());
+    }
+    header.extend(
+        deploys
+            .iter()
+            .map(|d| format!("[{} {}]", d.environment, d.country)),
+    );
+    let width = header.len();
+    writer.write_record(header)?;
+
+    let mut omitted = 0;
+    for row in rows {
+        if only_diff && !row.differs() {
+            omitted += 1;
+            continue;
+        }
+        trace!(
+            "unique {}: {}.{} {}",
+            kind.label(),
+            row.schema,
+            row.table,
+            row.name
+        );
+        let mut record = vec![row.schema];
+        if kind.in_table() {
+            record.push(row.table);
+        }
+        if kind.named() {
+            record.push(row.name);
+        }
+        if kind == ObjectKind::Routine {
+            record.push(row.routine_type);
+        }
+        for cell in row.cells {
+            record.push(if cell.unreachable {
+                UNREACHABLE.to_string()
+            } else if cell.differs {
+                format!("**{}", cell.value)
+            } else {
+                cell.value
+            });
+        }
+        writer.write_record(record)?;
+    }
+
+    if only_diff {
+        info!(
+            "{} identical {} rows omitted from {}",
+            omitted,
+            kind.label(),
+            file
+        );
+        let mut footer = vec![String::new(); width];
+        footer[0] = format!("{} identical objects omitted", omitted);
+        writer.write_record(footer)?;
+    }
+
+    writer.flush()?;
+    Ok(())
+}
+
+/// CSV writer of `file` quoting fields as RFC 4180, rows are written as they are built
+fn csv_writer(file: &str, delimiter: u8) -> Result<csv::Writer<BufWriter<File>>> {
+    let writer = csv::WriterBuilder::new()
+        .delimiter(delimiter)
+        .from_writer(create_output(file)?);
+    Ok(writer)
+}
+
+/// Create or truncate an output file, along with its directory
+pub(crate) fn create_output(file: &str) -> Result<BufWriter<File>> {
+    if let Some(parent) = Path::new(file).parent().filter(|p| !p.exists()) {
+        fs::create_dir_all(parent)?;
+    }
+    Ok(BufWriter::new(File::create(file)?))
 }
 
 // Read MySQL connection configuration from file
@@ -412,3 +1053,12 @@ pub fn read_config(config_path: &str) -> Result<Vec<ConnectInfo>> {
 
     Ok(config)
 }
+
+/// Read comparison rules, attributes absent from the file keep their default
+pub fn read_rules(rules_path: &str) -> Result<CompareRules> {
+    let json_rules = &fs::read_to_string(rules_path)?;
+
+    let rules: CompareRules = serde_json::from_str(json_rules)?;
+
+    Ok(rules)
+}
@@ -0,0 +1,408 @@
+use std::collections::BTreeMap;
+
+use super::collector::{
+    column_format, find_foreign_key_columns, find_index_columns, find_most_used_column,
+    find_most_used_foreign_key, find_most_used_index, find_most_used_table, find_table,
+    foreign_key_format, group_columns, group_foreign_keys, group_indices, group_tables,
+    index_format, table_format, MISSING, UNREACHABLE,
+};
+use super::event::{event_format, find_event, find_most_used_event, group_events};
+use super::partition::{find_most_used_partition, group_partitions, partition_value};
+use super::routine::{find_most_used_routine, find_routine, group_routines, routine_format};
+use super::trigger::{find_most_used_trigger, find_trigger, group_triggers, trigger_format};
+use super::view::{find_most_used_view, find_view, group_views, view_format};
+use crate::value_object::{
+    ColumnInfo, ColumnRules, Deploy, DiffCell, DiffRow, EventInfo, ForeignKeyInfo, IndexInfo,
+    ObjectKind, PartitionInfo, RoutineInfo, TableInfo, TriggerInfo, UniqueTable, ViewInfo,
+};
+
+/// What deploys are compared with, for report titles
+pub(crate) fn reference_name(deploys: &[Deploy]) -> String {
+    match deploys.iter().find(|d| d.baseline && !d.unreachable) {
+        Some(baseline) => format!("baseline [{} {}]", baseline.environment, baseline.country),
+        None => "the most used definition".to_string(),
+    }
+}
+
+/// Name of the group of a row in reports, the table or the schema for objects of the schema
+pub(crate) fn group_name(schema: &str, table: &str) -> String {
+    if table.is_empty() {
+        schema.to_string()
+    } else {
+        format!("{}.{}", schema, table)
+    }
+}
+
+/// Text of a cell in reports, objects absent from the deploy are shown as `MISSING`
+pub(crate) fn display_value(cell: &DiffCell) -> String {
+    if cell.unreachable {
+        UNREACHABLE.to_string()
+    } else if cell.differs && cell.value.is_empty() {
+        MISSING.to_string()
+    } else {
+        cell.value.clone()
+    }
+}
+
+/// The object doesn't exist in the deploy
+pub(crate) fn is_missing(cell: &DiffCell) -> bool {
+    !cell.unreachable && (cell.value.is_empty() || cell.value == MISSING)
+}
+
+/// The row only differs in deploys missing its whole table, which the table comparison and the
+/// missing tables output already report. Tables absent from `tables` in every deploy, e.g. of
+/// snapshots without table info, are never considered missing.
+fn only_missing_table(
+    row: &DiffRow,
+    tables: &BTreeMap<UniqueTable, Vec<&TableInfo>>,
+    deploys: &[Deploy],
+) -> bool {
+    let list = match tables.get(&UniqueTable {
+        schema: &row.schema,
+        table: &row.table,
+    }) {
+        Some(list) => list,
+        None => return false,
+    };
+    let mut missing = false;
+    let mut values: Vec<&str> = vec![];
+    for (deploy, cell) in deploys.iter().zip(&row.cells) {
+        if cell.unreachable {
+            continue;
+        }
+        if find_table(list, deploy).is_none() {
+            missing = true;
+        } else {
+            values.push(&cell.value);
+        }
+    }
+
+    row.differs() && missing && values.windows(2).all(|pair| pair[0] == pair[1])
+}
+
+/// Compare table options of every table, tables absent from a deploy are `MISSING`
+pub fn table_rows<'a>(
+    tables: &'a [TableInfo],
+    deploys: &'a [Deploy],
+) -> impl Iterator<Item = DiffRow> + 'a {
+    group_tables(tables).into_iter().map(move |(key, list)| {
+        let reference = find_most_used_table(deploys, &list);
+        let cells = deploys
+            .iter()
+            .map(|deploy| {
+                if deploy.unreachable {
+                    return DiffCell::unreachable();
+                }
+                let value = find_table(&list, deploy)
+                    .map(table_format)
+                    .unwrap_or_else(|| MISSING.to_string());
+                DiffCell::new(value, &reference)
+            })
+            .collect();
+
+        DiffRow {
+            kind: ObjectKind::Table,
+            schema: key.schema.to_string(),
+            table: key.table.to_string(),
+            name: String::new(),
+            reference,
+            cells,
+            routine_type: String::new(),
+        }
+    })
+}
+
+/// Compare column definitions according to `rules`, leaving out columns which only differ by
+/// their table being missing
+pub fn column_rows<'a>(
+    columns: &'a [ColumnInfo],
+    tables: &'a [TableInfo],
+    deploys: &'a [Deploy],
+    rules: &'a ColumnRules,
+) -> impl Iterator<Item = DiffRow> + 'a {
+    let tables = group_tables(tables);
+    group_columns(columns)
+        .into_iter()
+        .map(move |(key, list)| {
+            let reference = find_most_used_column(deploys, &list, rules);
+            let cells = deploys
+                .iter()
+                .map(|deploy| {
+                    if deploy.unreachable {
+                        return DiffCell::unreachable();
+                    }
+                    let value = list
+                        .iter()
+                        .find(|c| {
+                            c.environment == deploy.environment && c.country == deploy.country
+                        })
+                        .map(|c| column_format(c, rules))
+                        .unwrap_or_default();
+                    DiffCell::new(value, &reference)
+                })
+                .collect();
+
+            DiffRow {
+                kind: ObjectKind::Column,
+                schema: key.schema.to_string(),
+                table: key.table.to_string(),
+                name: key.column_name.to_string(),
+                reference,
+                cells,
+                routine_type: String::new(),
+            }
+        })
+        .filter(move |row| !only_missing_table(row, &tables, deploys))
+}
+
+/// Compare index columns and uniqueness, leaving out indices which only differ by their table
+/// being missing
+pub fn index_rows<'a>(
+    indices: &'a [IndexInfo],
+    tables: &'a [TableInfo],
+    deploys: &'a [Deploy],
+) -> impl Iterator<Item = DiffRow> + 'a {
+    let tables = group_tables(tables);
+    group_indices(indices)
+        .into_iter()
+        .map(move |(key, list)| {
+            let reference = find_most_used_index(deploys, &list);
+            let cells = deploys
+                .iter()
+                .map(|deploy| {
+                    if deploy.unreachable {
+                        return DiffCell::unreachable();
+                    }
+                    let columns = find_index_columns(&list, deploy);
+                    let value = if columns.is_empty() {
+                        String::new()
+                    } else {
+                        index_format(&columns)
+                    };
+                    DiffCell::new(value, &reference)
+                })
+                .collect();
+
+            DiffRow {
+                kind: ObjectKind::Index,
+                schema: key.schema.to_string(),
+                table: key.table.to_string(),
+                name: key.index_name.to_string(),
+                reference,
+                cells,
+                routine_type: String::new(),
+            }
+        })
+        .filter(move |row| !only_missing_table(row, &tables, deploys))
+}
+
+/// Compare foreign key columns, references and rules, leaving out foreign keys which only differ
+/// by their table being missing
+pub fn foreign_key_rows<'a>(
+    foreign_keys: &'a [ForeignKeyInfo],
+    tables: &'a [TableInfo],
+    deploys: &'a [Deploy],
+) -> impl Iterator<Item = DiffRow> + 'a {
+    let tables = group_tables(tables);
+    group_foreign_keys(foreign_keys)
+        .into_iter()
+        .map(move |(key, list)| {
+            let reference = find_most_used_foreign_key(deploys, &list);
+            let cells = deploys
+                .iter()
+                .map(|deploy| {
+                    if deploy.unreachable {
+                        return DiffCell::unreachable();
+                    }
+                    let columns = find_foreign_key_columns(&list, deploy);
+                    let value = if columns.is_empty() {
+                        String::new()
+                    } else {
+                        foreign_key_format(&columns)
+                    };
+                    DiffCell::new(value, &reference)
+                })
+                .collect();
+
+            DiffRow {
+                kind: ObjectKind::ForeignKey,
+                schema: key.schema.to_string(),
+                table: key.table.to_string(),
+                name: key.constraint_name.to_string(),
+                reference,
+                cells,
+                routine_type: String::new(),
+            }
+        })
+        .filter(move |row| !only_missing_table(row, &tables, deploys))
+}
+
+/// Compare partitioning schemes of tables partitioned in any deploy, tables without partitions
+/// are `NOT PARTITIONED`. Schemes which only differ by their table being missing are left out.
+pub fn partition_rows<'a>(
+    partitions: &'a [PartitionInfo],
+    tables: &'a [TableInfo],
+    deploys: &'a [Deploy],
+    ignore_boundaries: bool,
+) -> impl Iterator<Item = DiffRow> + 'a {
+    let grouped = group_tables(tables);
+    group_partitions(partitions)
+        .into_iter()
+        .map(move |(key, list)| {
+            let reference =
+                find_most_used_partition(deploys, &list, &key, tables, ignore_boundaries);
+            let cells = deploys
+                .iter()
+                .map(|deploy| {
+                    if deploy.unreachable {
+                        return DiffCell::unreachable();
+                    }
+                    let value = partition_value(&list, &key, tables, deploy, ignore_boundaries);
+                    DiffCell::new(value, &reference)
+                })
+                .collect();
+
+            DiffRow {
+                kind: ObjectKind::Partition,
+                schema: key.schema.to_string(),
+                table: key.table.to_string(),
+                name: String::new(),
+                reference,
+                cells,
+                routine_type: String::new(),
+            }
+        })
+        .filter(move |row| !only_missing_table(row, &grouped, deploys))
+}
+
+/// Compare trigger timing, events and normalised statements
+pub fn trigger_rows<'a>(
+    triggers: &'a [TriggerInfo],
+    deploys: &'a [Deploy],
+) -> impl Iterator<Item = DiffRow> + 'a {
+    group_triggers(triggers)
+        .into_iter()
+        .map(move |(key, list)| {
+            let reference = find_most_used_trigger(deploys, &list);
+            let cells = deploys
+                .iter()
+                .map(|deploy| {
+                    if deploy.unreachable {
+                        return DiffCell::unreachable();
+                    }
+                    let value = find_trigger(&list, deploy)
+                        .map(|t| trigger_format(t, deploy.engine))
+                        .unwrap_or_default();
+                    DiffCell::new(value, &reference)
+                })
+                .collect();
+
+            DiffRow {
+                kind: ObjectKind::Trigger,
+                schema: key.schema.to_string(),
+                table: key.table.to_string(),
+                name: key.trigger_name.to_string(),
+                reference,
+                cells,
+                routine_type: String::new(),
+            }
+        })
+}
+
+/// Compare view columns, options and normalised definitions
+pub fn view_rows<'a>(
+    views: &'a [ViewInfo],
+    deploys: &'a [Deploy],
+) -> impl Iterator<Item = DiffRow> + 'a {
+    group_views(views).into_iter().map(move |(key, list)| {
+        let reference = find_most_used_view(deploys, &list);
+        let cells = deploys
+            .iter()
+            .map(|deploy| {
+                if deploy.unreachable {
+                    return DiffCell::unreachable();
+                }
+                let value = find_view(&list, deploy)
+                    .map(|v| view_format(v, deploy.engine))
+                    .unwrap_or_default();
+                DiffCell::new(value, &reference)
+            })
+            .collect();
+
+        DiffRow {
+            kind: ObjectKind::View,
+            schema: key.schema.to_string(),
+            table: String::new(),
+            name: key.view_name.to_string(),
+            reference,
+            cells,
+            routine_type: String::new(),
+        }
+    })
+}
+
+/// Compare routine signatures and normalised definitions
+pub fn routine_rows<'a>(
+    routines: &'a [RoutineInfo],
+    deploys: &'a [Deploy],
+) -> impl Iterator<Item = DiffRow> + 'a {
+    group_routines(routines)
+        .into_iter()
+        .map(move |(key, list)| {
+            let reference = find_most_used_routine(deploys, &list);
+            let cells = deploys
+                .iter()
+                .map(|deploy| {
+                    if deploy.unreachable {
+                        return DiffCell::unreachable();
+                    }
+                    let value = find_routine(&list, deploy)
+                        .map(|r| routine_format(r, deploy.engine))
+                        .unwrap_or_default();
+                    DiffCell::new(value, &reference)
+                })
+                .collect();
+
+            DiffRow {
+                kind: ObjectKind::Routine,
+                schema: key.schema.to_string(),
+                table: String::new(),
+                name: key.routine_name.to_string(),
+                reference,
+                cells,
+                routine_type: key.routine_type.to_string(),
+            }
+        })
+}
+
+/// Compare event schedules, status and normalised bodies
+pub fn event_rows<'a>(
+    events: &'a [EventInfo],
+    deploys: &'a [Deploy],
+) -> impl Iterator<Item = DiffRow> + 'a {
+    group_events(events).into_iter().map(move |(key, list)| {
+        let reference = find_most_used_event(deploys, &list);
+        let cells = deploys
+            .iter()
+            .map(|deploy| {
+                if deploy.unreachable {
+                    return DiffCell::unreachable();
+                }
+                let value = find_event(&list, deploy)
+                    .map(|e| event_format(e, deploy.engine))
+                    .unwrap_or_default();
+                DiffCell::new(value, &reference)
+            })
+            .collect();
+
+        DiffRow {
+            kind: ObjectKind::Event,
+            schema: key.schema.to_string(),
+            table: String::new(),
+            name: key.event_name.to_string(),
+            reference,
+            cells,
+            routine_type: String::new(),
+        }
+    })
+}
@@ -0,0 +1,40 @@
+mod mysql;
+mod postgres;
+mod sqlite;
+
+pub use self::mysql::MysqlCollector;
+pub use self::postgres::PostgresCollector;
+pub use self::sqlite::SqliteCollector;
+
+use std::time::Duration;
+
+use crate::value_object::{
+    ColumnInfo, ConnectInfo, Engine, EventInfo, ForeignKeyInfo, IndexInfo, PartitionInfo, Result,
+    RoutineInfo, TableInfo, TriggerInfo, ViewInfo,
+};
+
+/// Source of schema info of one configured database
+pub trait SchemaCollector {
+    fn collect_tables(&mut self) -> Result<Vec<TableInfo>>;
+    fn collect_columns(&mut self) -> Result<Vec<ColumnInfo>>;
+    fn collect_indices(&mut self) -> Result<Vec<IndexInfo>>;
+    fn collect_foreign_keys(&mut self) -> Result<Vec<ForeignKeyInfo>>;
+    fn collect_partitions(&mut self) -> Result<Vec<PartitionInfo>>;
+    fn collect_triggers(&mut self) -> Result<Vec<TriggerInfo>>;
+    fn collect_views(&mut self) -> Result<Vec<ViewInfo>>;
+    fn collect_routines(&mut self) -> Result<Vec<RoutineInfo>>;
+    fn collect_events(&mut self) -> Result<Vec<EventInfo>>;
+}
+
+/// Connect to the database with the collector of its engine, `timeout` applies to connecting
+/// and to each query
+pub fn connect(
+    config: &ConnectInfo,
+    timeout: Option<Duration>,
+) -> Result<Box<dyn SchemaCollector + '_>> {
+    Ok(match config.engine {
+        Engine::Mysql => Box::new(MysqlCollector::connect(config, timeout)?),
+        Engine::Postgres => Box::new(PostgresCollector::connect(config, timeout)?),
+        Engine::Sqlite => Box::new(SqliteCollector::connect(config, timeout)?),
+    })
+}
@@ -0,0 +1,415 @@
+use log::trace;
+use mysql::{params, prelude::Queryable, Opts, OptsBuilder, Pool, PooledConn};
+use std::{include_str, time::Duration};
+
+use super::SchemaCollector;
+use crate::value_object::{
+    ColumnInfo, ConnectInfo, EventInfo, ForeignKeyInfo, IndexInfo, PartitionInfo, Result,
+    RoutineInfo, TableInfo, TriggerInfo, ViewInfo,
+};
+
+/// Collect schema info from MySQL `information_schema`
+pub struct MysqlCollector<'a> {
+    config: &'a ConnectInfo,
+    conn: PooledConn,
+}
+
+impl<'a> MysqlCollector<'a> {
+    pub fn connect(config: &'a ConnectInfo, timeout: Option<Duration>) -> Result<Self> {
+        let url = format!(
+            "mysql://{}:{}@{}:{}/{}",
+            config.user, config.password, config.host, config.port, config.schema
+        );
+        let opts = OptsBuilder::from_opts(Opts::from_url(&url)?)
+            .tcp_connect_timeout(timeout)
+            .read_timeout(timeout)
+            .write_timeout(timeout);
+        let pool = Pool::new(opts)?;
+        let conn = pool.get_conn()?;
+        Ok(MysqlCollector { config, conn })
+    }
+}
+
+impl SchemaCollector for MysqlCollector<'_> {
+    fn collect_tables(&mut self) -> Result<Vec<TableInfo>> {
+        let config = self.config;
+        let conn = &mut self.conn;
+        let query = include_str!("../../../resources/list-mysql-tables.sql");
+
+        let stmt = conn.prep(query)?;
+        let result = conn.exec_map(
+            stmt,
+            params! { "schema" => config.schema.clone() },
+            |mut row: mysql::Row| {
+                trace!("{:?}", row);
+                let auto_increment: Option<u64> = row
+                    .get_opt("AUTO_INCREMENT")
+                    .unwrap()
+                    .unwrap_or(Option::None);
+
+                TableInfo {
+                    country: config.country.clone(),
+                    environment: config.environment.clone(),
+                    schema: config.schema.clone(),
+
+                    table: row.take("TABLE_NAME").unwrap(),
+                    engine: row.get_opt("ENGINE").unwrap().unwrap_or(Option::None),
+                    collation: row
+                        .get_opt("TABLE_COLLATION")
+                        .unwrap()
+                        .unwrap_or(Option::None),
+                    row_format: row.get_opt("ROW_FORMAT").unwrap().unwrap_or(Option::None),
+                    auto_increment: auto_increment.is_some(),
+                    comment: row
+                        .get_opt("TABLE_COMMENT")
+                        .unwrap()
+                        .unwrap_or(Option::None),
+
+                    display: Option::None,
+                }
+            },
+        )?;
+
+        Ok(result)
+    }
+
+    fn collect_columns(&mut self) -> Result<Vec<ColumnInfo>> {
+        let config = self.config;
+        let conn = &mut self.conn;
+        let query = include_str!("../../../resources/list-mysql-columns.sql");
+
+        let stmt = conn.prep(query)?;
+        let result = conn.exec_map(
+            stmt,
+            params! { "schema" => config.schema.clone() },
+            |mut row: mysql::Row| {
+                trace!("{:?}", row);
+
+                ColumnInfo {
+                    country: config.country.clone(),
+                    environment: config.environment.clone(),
+                    schema: config.schema.clone(),
+
+                    table: row.take("TABLE_NAME").unwrap(),
+                    column_name: row.take("COLUMN_NAME").unwrap(),
+                    column_type: row.take("COLUMN_TYPE").unwrap(),
+                    data_type: row.take("DATA_TYPE").unwrap(),
+                    text_max_length: row
+                        .get_opt("CHARACTER_MAXIMUM_LENGTH")
+                        .unwrap()
+                        .unwrap_or(Option::None),
+                    text_octet_length: row
+                        .get_opt("CHARACTER_OCTET_LENGTH")
+                        .unwrap()
+                        .unwrap_or(Option::None),
+                    num_precision: row
+                        .get_opt("NUMERIC_PRECISION")
+                        .unwrap()
+                        .unwrap_or(Option::None),
+                    default_value: row
+                        .get_opt("COLUMN_DEFAULT")
+                        .unwrap()
+                        .unwrap_or(Option::None),
+                    nullable: row.take("IS_NULLABLE").unwrap(),
+                    num_scale: row
+                        .get_opt("NUMERIC_SCALE")
+                        .unwrap()
+                        .unwrap_or(Option::None),
+                    charset: row
+                        .get_opt("CHARACTER_SET_NAME")
+                        .unwrap()
+                        .unwrap_or(Option::None),
+                    collation: row
+                        .get_opt("COLLATION_NAME")
+                        .unwrap()
+                        .unwrap_or(Option::None),
+                    comment: row
+                        .get_opt("COLUMN_COMMENT")
+                        .unwrap()
+                        .unwrap_or(Option::None),
+                    extra: row.get_opt("EXTRA").unwrap().unwrap_or(Option::None),
+                    ordinal_position: row.take("ORDINAL_POSITION").unwrap(),
+                    primary_key: row.take::<String, _>("PRIMARY_KEY").unwrap() == "YES",
+
+                    display: Option::None,
+                }
+            },
+        )?;
+
+        Ok(result)
+    }
+
+    fn collect_indices(&mut self) -> Result<Vec<IndexInfo>> {
+        let config = self.config;
+        let conn = &mut self.conn;
+        let query = include_str!("../../../resources/list-mysql-indices.sql");
+
+        let stmt = conn.prep(query)?;
+        let result = conn.exec_map(
+            stmt,
+            params! { "schema" => config.schema.clone() },
+            |mut row: mysql::Row| {
+                trace!("{:?}", row);
+                IndexInfo {
+                    country: config.country.clone(),
+                    environment: config.environment.clone(),
+                    schema: config.schema.clone(),
+
+                    table: row.take("TABLE_NAME").unwrap(),
+                    index_name: row.take("INDEX_NAME").unwrap(),
+                    unique: row.take::<i32, _>("NON_UNIQUE").unwrap() == 0,
+                    sequence: row.take("SEQ_IN_INDEX").unwrap(),
+                    column: row.take("COLUMN_NAME").unwrap(),
+
+                    display: Option::None,
+                }
+            },
+        )?;
+
+        Ok(result)
+    }
+
+    fn collect_foreign_keys(&mut self) -> Result<Vec<ForeignKeyInfo>> {
+        let config = self.config;
+        let conn = &mut self.conn;
+        let query = include_str!("../../../resources/list-mysql-foreign-keys.sql");
+
+        let stmt = conn.prep(query)?;
+        let result = conn.exec_map(
+            stmt,
+            params! { "schema" => config.schema.clone() },
+            |mut row: mysql::Row| {
+                trace!("{:?}", row);
+                ForeignKeyInfo {
+                    country: config.country.clone(),
+                    environment: config.environment.clone(),
+                    schema: config.schema.clone(),
+
+                    table: row.take("TABLE_NAME").unwrap(),
+                    constraint_name: row.take("CONSTRAINT_NAME").unwrap(),
+                    sequence: row.take("ORDINAL_POSITION").unwrap(),
+                    column: row.take("COLUMN_NAME").unwrap(),
+                    referenced_schema: row.take("REFERENCED_TABLE_SCHEMA").unwrap(),
+                    referenced_table: row.take("REFERENCED_TABLE_NAME").unwrap(),
+                    referenced_column: row.take("REFERENCED_COLUMN_NAME").unwrap(),
+                    update_rule: row.take("UPDATE_RULE").unwrap(),
+                    delete_rule: row.take("DELETE_RULE").unwrap(),
+
+                    display: Option::None,
+                }
+            },
+        )?;
+
+        Ok(result)
+    }
+
+    fn collect_partitions(&mut self) -> Result<Vec<PartitionInfo>> {
+        let config = self.config;
+        let conn = &mut self.conn;
+        let query = include_str!("../../../resources/list-mysql-partitions.sql");
+
+        let stmt = conn.prep(query)?;
+        let result = conn.exec_map(
+            stmt,
+            params! { "schema" => config.schema.clone() },
+            |mut row: mysql::Row| {
+                trace!("{:?}", row);
+                PartitionInfo {
+                    country: config.country.clone(),
+                    environment: config.environment.clone(),
+                    schema: config.schema.clone(),
+
+                    table: row.take("TABLE_NAME").unwrap(),
+                    partition_name: row.take("PARTITION_NAME").unwrap(),
+                    ordinal_position: row.take("PARTITION_ORDINAL_POSITION").unwrap(),
+                    method: row.take("PARTITION_METHOD").unwrap(),
+                    expression: row
+                        .get_opt("PARTITION_EXPRESSION")
+                        .unwrap()
+                        .unwrap_or(Option::None),
+                    bound: row
+                        .get_opt("PARTITION_BOUND")
+                        .unwrap()
+                        .unwrap_or(Option::None),
+                    subpartition_method: row
+                        .get_opt("SUBPARTITION_METHOD")
+                        .unwrap()
+                        .unwrap_or(Option::None),
+                    subpartition_expression: row
+                        .get_opt("SUBPARTITION_EXPRESSION")
+                        .unwrap()
+                        .unwrap_or(Option::None),
+                    subpartitions: row.take("SUBPARTITIONS").unwrap(),
+
+                    display: Option::None,
+                }
+            },
+        )?;
+
+        Ok(result)
+    }
+
+    fn collect_triggers(&mut self) -> Result<Vec<TriggerInfo>> {
+        let config = self.config;
+        let conn = &mut self.conn;
+        let query = include_str!("../../../resources/list-mysql-triggers.sql");
+
+        let stmt = conn.prep(query)?;
+        let result = conn.exec_map(
+            stmt,
+            params! { "schema" => config.schema.clone() },
+            |mut row: mysql::Row| {
+                trace!("{:?}", row);
+                TriggerInfo {
+                    country: config.country.clone(),
+                    environment: config.environment.clone(),
+                    schema: config.schema.clone(),
+
+                    table: row.take("EVENT_OBJECT_TABLE").unwrap(),
+                    trigger_name: row.take("TRIGGER_NAME").unwrap(),
+                    timing: row.take("ACTION_TIMING").unwrap(),
+                    event: row.take("EVENT_MANIPULATION").unwrap(),
+                    statement: row.take("ACTION_STATEMENT").unwrap(),
+                    definer: row.get_opt("DEFINER").unwrap().unwrap_or(Option::None),
+
+                    display: Option::None,
+                }
+            },
+        )?;
+
+        Ok(result)
+    }
+
+    fn collect_views(&mut self) -> Result<Vec<ViewInfo>> {
+        let config = self.config;
+        let conn = &mut self.conn;
+        let query = include_str!("../../../resources/list-mysql-views.sql");
+
+        let stmt = conn.prep(query)?;
+        let result = conn.exec_map(
+            stmt,
+            params! { "schema" => config.schema.clone() },
+            |mut row: mysql::Row| {
+                trace!("{:?}", row);
+                ViewInfo {
+                    country: config.country.clone(),
+                    environment: config.environment.clone(),
+                    schema: config.schema.clone(),
+
+                    view_name: row.take("TABLE_NAME").unwrap(),
+                    columns: row
+                        .get_opt("VIEW_COLUMNS")
+                        .unwrap()
+                        .unwrap_or(Option::None)
+                        .unwrap_or_default(),
+                    definition: row.take("VIEW_DEFINITION").unwrap(),
+                    check_option: row.take("CHECK_OPTION").unwrap(),
+                    security_type: row
+                        .get_opt("SECURITY_TYPE")
+                        .unwrap()
+                        .unwrap_or(Option::None),
+                    updatable: row.take::<String, _>("IS_UPDATABLE").unwrap() == "YES",
+
+                    display: Option::None,
+                }
+            },
+        )?;
+
+        Ok(result)
+    }
+
+    fn collect_routines(&mut self) -> Result<Vec<RoutineInfo>> {
+        let config = self.config;
+        let conn = &mut self.conn;
+        let query = include_str!("../../../resources/list-mysql-routines.sql");
+
+        let stmt = conn.prep(query)?;
+        let mut result = conn.exec_map(
+            stmt,
+            params! { "schema" => config.schema.clone() },
+            |mut row: mysql::Row| {
+                trace!("{:?}", row);
+                RoutineInfo {
+                    country: config.country.clone(),
+                    environment: config.environment.clone(),
+                    schema: config.schema.clone(),
+
+                    routine_type: row.take("ROUTINE_TYPE").unwrap(),
+                    routine_name: row.take("ROUTINE_NAME").unwrap(),
+                    parameters: row
+                        .get_opt("PARAMETERS")
+                        .unwrap()
+                        .unwrap_or(Option::None)
+                        .unwrap_or_default(),
+                    returns: row.get_opt("RETURNS").unwrap().unwrap_or(Option::None),
+                    definition: row
+                        .get_opt("ROUTINE_DEFINITION")
+                        .unwrap()
+                        .unwrap_or(Option::None)
+                        .unwrap_or_default(),
+
+                    display: Option::None,
+                }
+            },
+        )?;
+
+        // the create statement keeps comments and the signature, it's null without privileges
+        for routine in result.iter_mut() {
+            let query = format!(
+                "SHOW CREATE {} `{}`.`{}`",
+                routine.routine_type, routine.schema, routine.routine_name
+            );
+            let row: Option<mysql::Row> = conn.query_first(query)?;
+            let statement: Option<String> = row.and_then(|mut row| {
+                let column = format!("Create {}", titlecase(&routine.routine_type));
+                row.take_opt(column.as_str()).and_then(|v| v.ok())
+            });
+            if let Some(statement) = statement {
+                routine.definition = statement;
+            }
+        }
+
+        Ok(result)
+    }
+
+    fn collect_events(&mut self) -> Result<Vec<EventInfo>> {
+        let config = self.config;
+        let conn = &mut self.conn;
+        let query = include_str!("../../../resources/list-mysql-events.sql");
+
+        let stmt = conn.prep(query)?;
+        let result = conn.exec_map(
+            stmt,
+            params! { "schema" => config.schema.clone() },
+            |mut row: mysql::Row| {
+                trace!("{:?}", row);
+                EventInfo {
+                    country: config.country.clone(),
+                    environment: config.environment.clone(),
+                    schema: config.schema.clone(),
+
+                    event_name: row.take("EVENT_NAME").unwrap(),
+                    schedule: row.take("SCHEDULE").unwrap(),
+                    time_zone: row.take("TIME_ZONE").unwrap(),
+                    status: row.take("STATUS").unwrap(),
+                    on_completion: row.take("ON_COMPLETION").unwrap(),
+                    body: row.take("EVENT_DEFINITION").unwrap(),
+
+                    display: Option::None,
+                }
+            },
+        )?;
+
+        Ok(result)
+    }
+}
+
+/// `PROCEDURE` to `Procedure`, as in the column names of `SHOW CREATE`
+fn titlecase(value: &str) -> String {
+    let lower = value.to_lowercase();
+    let mut chars = lower.chars();
+    match chars.next() {
+        Some(first) => first.to_uppercase().chain(chars).collect(),
+        None => String::new(),
+    }
+}
@@ -0,0 +1,302 @@
+use log::trace;
+use postgres::{Client, NoTls};
+use std::{include_str, time::Duration};
+
+use super::SchemaCollector;
+use crate::value_object::{
+    ColumnInfo, ConnectInfo, EventInfo, ForeignKeyInfo, IndexInfo, PartitionInfo, Result,
+    RoutineInfo, TableInfo, TriggerInfo, ViewInfo,
+};
+
+/// Collect schema info from PostgreSQL `information_schema` and `pg_catalog`
+pub struct PostgresCollector<'a> {
+    config: &'a ConnectInfo,
+    client: Client,
+}
+
+impl<'a> PostgresCollector<'a> {
+    pub fn connect(config: &'a ConnectInfo, timeout: Option<Duration>) -> Result<Self> {
+        let mut pg_config = postgres::Config::new();
+        pg_config
+            .host(&config.host)
+            .port(config.port as u16)
+            .user(&config.user)
+            .password(&config.password)
+            .dbname(config.database.as_ref().unwrap_or(&config.user));
+        if let Some(timeout) = timeout {
+            pg_config
+                .connect_timeout(timeout)
+                .options(&format!("-c statement_timeout={}", timeout.as_millis()));
+        }
+        let client = pg_config.connect(NoTls)?;
+        Ok(PostgresCollector { config, client })
+    }
+}
+
+impl SchemaCollector for PostgresCollector<'_> {
+    fn collect_tables(&mut self) -> Result<Vec<TableInfo>> {
+        let config = self.config;
+        let query = include_str!("../../../resources/list-postgres-tables.sql");
+
+        let result = self
+            .client
+            .query(query, &[&config.schema])?
+            .into_iter()
+            .map(|row| {
+                trace!("{:?}", row);
+                TableInfo {
+                    country: config.country.clone(),
+                    environment: config.environment.clone(),
+                    schema: config.schema.clone(),
+
+                    table: row.get("table_name"),
+                    engine: row.get("engine"),
+                    collation: row.get("table_collation"),
+                    row_format: row.get("row_format"),
+                    auto_increment: row.get("auto_increment"),
+                    comment: row.get("table_comment"),
+
+                    display: Option::None,
+                }
+            })
+            .collect();
+
+        Ok(result)
+    }
+
+    fn collect_columns(&mut self) -> Result<Vec<ColumnInfo>> {
+        let config = self.config;
+        let query = include_str!("../../../resources/list-postgres-columns.sql");
+
+        let result = self
+            .client
+            .query(query, &[&config.schema])?
+            .into_iter()
+            .map(|row| {
+                trace!("{:?}", row);
+
+                ColumnInfo {
+                    country: config.country.clone(),
+                    environment: config.environment.clone(),
+                    schema: config.schema.clone(),
+
+                    table: row.get("table_name"),
+                    column_name: row.get("column_name"),
+                    column_type: row.get("column_type"),
+                    data_type: row.get("data_type"),
+                    text_max_length: row.get("character_maximum_length"),
+                    text_octet_length: row.get("character_octet_length"),
+                    num_precision: row.get("numeric_precision"),
+                    default_value: row.get("column_default"),
+                    nullable: row.get("is_nullable"),
+                    num_scale: row.get("numeric_scale"),
+                    charset: row.get("character_set_name"),
+                    collation: row.get("collation_name"),
+                    comment: row.get("column_comment"),
+                    extra: row.get("extra"),
+                    ordinal_position: row.get("ordinal_position"),
+                    primary_key: row.get("primary_key"),
+
+                    display: Option::None,
+                }
+            })
+            .collect();
+
+        Ok(result)
+    }
+
+    fn collect_indices(&mut self) -> Result<Vec<IndexInfo>> {
+        let config = self.config;
+        let query = include_str!("../../../resources/list-postgres-indices.sql");
+
+        let result = self
+            .client
+            .query(query, &[&config.schema])?
+            .into_iter()
+            .map(|row| {
+                trace!("{:?}", row);
+                IndexInfo {
+                    country: config.country.clone(),
+                    environment: config.environment.clone(),
+                    schema: config.schema.clone(),
+
+                    table: row.get("table_name"),
+                    index_name: row.get("index_name"),
+                    unique: row.get("is_unique"),
+                    sequence: row.get("seq_in_index"),
+                    column: row.get("column_name"),
+
+                    display: Option::None,
+                }
+            })
+            .collect();
+
+        Ok(result)
+    }
+
+    fn collect_foreign_keys(&mut self) -> Result<Vec<ForeignKeyInfo>> {
+        let config = self.config;
+        let query = include_str!("../../../resources/list-postgres-foreign-keys.sql");
+
+        let result = self
+            .client
+            .query(query, &[&config.schema])?
+            .into_iter()
+            .map(|row| {
+                trace!("{:?}", row);
+                ForeignKeyInfo {
+                    country: config.country.clone(),
+                    environment: config.environment.clone(),
+                    schema: config.schema.clone(),
+
+                    table: row.get("table_name"),
+                    constraint_name: row.get("constraint_name"),
+                    sequence: row.get("ordinal_position"),
+                    column: row.get("column_name"),
+                    referenced_schema: row.get("referenced_table_schema"),
+                    referenced_table: row.get("referenced_table_name"),
+                    referenced_column: row.get("referenced_column_name"),
+                    update_rule: row.get("update_rule"),
+                    delete_rule: row.get("delete_rule"),
+
+                    display: Option::None,
+                }
+            })
+            .collect();
+
+        Ok(result)
+    }
+
+    fn collect_partitions(&mut self) -> Result<Vec<PartitionInfo>> {
+        let config = self.config;
+        let query = include_str!("../../../resources/list-postgres-partitions.sql");
+
+        let result = self
+            .client
+            .query(query, &[&config.schema])?
+            .into_iter()
+            .map(|row| {
+                trace!("{:?}", row);
+                PartitionInfo {
+                    country: config.country.clone(),
+                    environment: config.environment.clone(),
+                    schema: config.schema.clone(),
+
+                    table: row.get("table_name"),
+                    partition_name: row.get("partition_name"),
+                    ordinal_position: row.get("ordinal_position"),
+                    method: row.get("partition_method"),
+                    expression: row.get("partition_expression"),
+                    bound: row.get("partition_bound"),
+                    // sub-partitions are partitioned tables of their own
+                    subpartition_method: Option::None,
+                    subpartition_expression: Option::None,
+                    subpartitions: 0,
+
+                    display: Option::None,
+                }
+            })
+            .collect();
+
+        Ok(result)
+    }
+
+    fn collect_triggers(&mut self) -> Result<Vec<TriggerInfo>> {
+        let config = self.config;
+        let query = include_str!("../../../resources/list-postgres-triggers.sql");
+
+        let result = self
+            .client
+            .query(query, &[&config.schema])?
+            .into_iter()
+            .map(|row| {
+                trace!("{:?}", row);
+                TriggerInfo {
+                    country: config.country.clone(),
+                    environment: config.environment.clone(),
+                    schema: config.schema.clone(),
+
+                    table: row.get("event_object_table"),
+                    trigger_name: row.get("trigger_name"),
+                    timing: row.get("action_timing"),
+                    event: row.get("event_manipulation"),
+                    statement: row.get("action_statement"),
+                    definer: Option::None,
+
+                    display: Option::None,
+                }
+            })
+            .collect();
+
+        Ok(result)
+    }
+
+    fn collect_views(&mut self) -> Result<Vec<ViewInfo>> {
+        let config = self.config;
+        let query = include_str!("../../../resources/list-postgres-views.sql");
+
+        let result = self
+            .client
+            .query(query, &[&config.schema])?
+            .into_iter()
+            .map(|row| {
+                trace!("{:?}", row);
+                ViewInfo {
+                    country: config.country.clone(),
+                    environment: config.environment.clone(),
+                    schema: config.schema.clone(),
+
+                    view_name: row.get("table_name"),
+                    columns: row
+                        .get::<_, Option<String>>("view_columns")
+                        .unwrap_or_default(),
+                    definition: row
+                        .get::<_, Option<String>>("view_definition")
+                        .unwrap_or_default(),
+                    check_option: row.get("check_option"),
+                    security_type: Option::None,
+                    updatable: row.get::<_, String>("is_updatable") == "YES",
+
+                    display: Option::None,
+                }
+            })
+            .collect();
+
+        Ok(result)
+    }
+
+    fn collect_routines(&mut self) -> Result<Vec<RoutineInfo>> {
+        let config = self.config;
+        let query = include_str!("../../../resources/list-postgres-routines.sql");
+
+        let result = self
+            .client
+            .query(query, &[&config.schema])?
+            .into_iter()
+            .map(|row| {
+                trace!("{:?}", row);
+                RoutineInfo {
+                    country: config.country.clone(),
+                    environment: config.environment.clone(),
+                    schema: config.schema.clone(),
+
+                    routine_type: row.get("routine_type"),
+                    // overloaded functions are told apart by their argument types
+                    routine_name: row.get("routine_name"),
+                    parameters: row.get("parameters"),
+                    returns: row.get("returns"),
+                    definition: row.get("definition"),
+
+                    display: Option::None,
+                }
+            })
+            .collect();
+
+        Ok(result)
+    }
+
+    /// PostgreSQL has no scheduled events
+    fn collect_events(&mut self) -> Result<Vec<EventInfo>> {
+        Ok(vec![])
+    }
+}
@@ -0,0 +1,337 @@
+use log::trace;
+use rusqlite::{Connection, OpenFlags};
+use std::{include_str, time::Duration};
+
+use super::SchemaCollector;
+use crate::value_object::{
+    ColumnInfo, ConnectInfo, EventInfo, ForeignKeyInfo, IndexInfo, PartitionInfo, Result,
+    RoutineInfo, TableInfo, TriggerInfo, ViewInfo,
+};
+
+/// Collect schema info from a SQLite database file with `PRAGMA` table functions
+pub struct SqliteCollector<'a> {
+    config: &'a ConnectInfo,
+    conn: Connection,
+}
+
+impl<'a> SqliteCollector<'a> {
+    pub fn connect(config: &'a ConnectInfo, timeout: Option<Duration>) -> Result<Self> {
+        let path = config
+            .database
+            .as_ref()
+            .ok_or_else(|| format!("Missing database file of [{}]", config.display_url()))?;
+        let conn = Connection::open_with_flags(path, OpenFlags::SQLITE_OPEN_READ_ONLY)?;
+        if let Some(timeout) = timeout {
+            conn.busy_timeout(timeout)?;
+        }
+        Ok(SqliteCollector { config, conn })
+    }
+}
+
+impl SchemaCollector for SqliteCollector<'_> {
+    fn collect_tables(&mut self) -> Result<Vec<TableInfo>> {
+        let config = self.config;
+        let query = include_str!("../../../resources/list-sqlite-tables.sql");
+
+        let mut stmt = self.conn.prepare(query)?;
+        let result = stmt
+            .query_map([], |row| {
+                trace!("{:?}", row);
+                Ok(TableInfo {
+                    country: config.country.clone(),
+                    environment: config.environment.clone(),
+                    schema: config.schema.clone(),
+
+                    table: row.get("TABLE_NAME")?,
+                    engine: Option::None,
+                    collation: Option::None,
+                    row_format: row.get("ROW_FORMAT")?,
+                    auto_increment: row.get("AUTO_INCREMENT")?,
+                    comment: Option::None,
+
+                    display: Option::None,
+                })
+            })?
+            .collect::<rusqlite::Result<Vec<TableInfo>>>()?;
+
+        Ok(result)
+    }
+
+    fn collect_columns(&mut self) -> Result<Vec<ColumnInfo>> {
+        let config = self.config;
+        let query = include_str!("../../../resources/list-sqlite-columns.sql");
+
+        let mut stmt = self.conn.prepare(query)?;
+        let result = stmt
+            .query_map([], |row| {
+                trace!("{:?}", row);
+                let column_type: String = row.get("COLUMN_TYPE")?;
+
+                Ok(ColumnInfo {
+                    country: config.country.clone(),
+                    environment: config.environment.clone(),
+                    schema: config.schema.clone(),
+
+                    table: row.get("TABLE_NAME")?,
+                    column_name: row.get("COLUMN_NAME")?,
+                    data_type: column_type
+                        .split('(')
+                        .next()
+                        .unwrap_or_default()
+                        .trim()
+                        .to_lowercase(),
+                    column_type,
+                    text_max_length: Option::None,
+                    text_octet_length: Option::None,
+                    num_precision: Option::None,
+                    default_value: row.get("COLUMN_DEFAULT")?,
+                    nullable: row.get("IS_NULLABLE")?,
+                    num_scale: Option::None,
+                    charset: Option::None,
+                    collation: Option::None,
+                    comment: Option::None,
+                    extra: Option::None,
+                    ordinal_position: row.get("ORDINAL_POSITION")?,
+                    primary_key: row.get::<_, i32>("PRIMARY_KEY")? > 0,
+
+                    display: Option::None,
+                })
+            })?
+            .collect::<rusqlite::Result<Vec<ColumnInfo>>>()?;
+
+        Ok(result)
+    }
+
+    fn collect_indices(&mut self) -> Result<Vec<IndexInfo>> {
+        let config = self.config;
+        let query = include_str!("../../../resources/list-sqlite-indices.sql");
+
+        let mut stmt = self.conn.prepare(query)?;
+        let result = stmt
+            .query_map([], |row| {
+                trace!("{:?}", row);
+                Ok(IndexInfo {
+                    country: config.country.clone(),
+                    environment: config.environment.clone(),
+                    schema: config.schema.clone(),
+
+                    table: row.get("TABLE_NAME")?,
+                    index_name: row.get("INDEX_NAME")?,
+                    unique: row.get("IS_UNIQUE")?,
+                    sequence: row.get("SEQ_IN_INDEX")?,
+                    column: row.get("COLUMN_NAME")?,
+
+                    display: Option::None,
+                })
+            })?
+            .collect::<rusqlite::Result<Vec<IndexInfo>>>()?;
+
+        Ok(result)
+    }
+
+    fn collect_foreign_keys(&mut self) -> Result<Vec<ForeignKeyInfo>> {
+        let config = self.config;
+        let query = include_str!("../../../resources/list-sqlite-foreign-keys.sql");
+
+        let mut stmt = self.conn.prepare(query)?;
+        let rows = stmt
+            .query_map([], |row| {
+                trace!("{:?}", row);
+                let id: i32 = row.get("CONSTRAINT_ID")?;
+                Ok((
+                    id,
+                    ForeignKeyInfo {
+                        country: config.country.clone(),
+                        environment: config.environment.clone(),
+                        schema: config.schema.clone(),
+
+                        table: row.get("TABLE_NAME")?,
+                        constraint_name: String::new(),
+                        sequence: row.get("ORDINAL_POSITION")?,
+                        column: row.get("COLUMN_NAME")?,
+                        // SQLite can't reference tables of another database
+                        referenced_schema: config.schema.clone(),
+                        referenced_table: row.get("REFERENCED_TABLE_NAME")?,
+                        referenced_column: row.get("REFERENCED_COLUMN_NAME")?,
+                        update_rule: row.get("UPDATE_RULE")?,
+                        delete_rule: row.get("DELETE_RULE")?,
+
+                        display: Option::None,
+                    },
+                ))
+            })?
+            .collect::<rusqlite::Result<Vec<(i32, ForeignKeyInfo)>>>()?;
+
+        // SQLite foreign keys are unnamed, name them after their table and columns instead of
+        // the id, which depends on declaration order
+        let names: Vec<String> = rows
+            .iter()
+            .map(|(id, foreign_key)| {
+                let columns = rows
+                    .iter()
+                    .filter(|(i, f)| i == id && f.table == foreign_key.table)
+                    .map(|(_, f)| f.column.as_str())
+                    .collect::<Vec<&str>>()
+                    .join("_");
+                format!("fk_{}_{}", foreign_key.table, columns)
+            })
+            .collect();
+
+        let result = rows
+            .into_iter()
+            .zip(names)
+            .map(|((_, mut foreign_key), name)| {
+                foreign_key.constraint_name = name;
+                foreign_key
+            })
+            .collect();
+
+        Ok(result)
+    }
+
+    /// SQLite has no partitioned tables
+    fn collect_partitions(&mut self) -> Result<Vec<PartitionInfo>> {
+        Ok(vec![])
+    }
+
+    fn collect_triggers(&mut self) -> Result<Vec<TriggerInfo>> {
+        let config = self.config;
+        let query = include_str!("../../../resources/list-sqlite-triggers.sql");
+
+        let mut stmt = self.conn.prepare(query)?;
+        let result = stmt
+            .query_map([], |row| {
+                trace!("{:?}", row);
+                let sql: String = row.get("sql")?;
+                let (timing, event, statement) = parse_trigger(&sql);
+                Ok(TriggerInfo {
+                    country: config.country.clone(),
+                    environment: config.environment.clone(),
+                    schema: config.schema.clone(),
+
+                    table: row.get("TABLE_NAME")?,
+                    trigger_name: row.get("TRIGGER_NAME")?,
+                    timing,
+                    event,
+                    statement,
+                    definer: Option::None,
+
+                    display: Option::None,
+                })
+            })?
+            .collect::<rusqlite::Result<Vec<TriggerInfo>>>()?;
+
+        Ok(result)
+    }
+
+    fn collect_views(&mut self) -> Result<Vec<ViewInfo>> {
+        let config = self.config;
+        let query = include_str!("../../../resources/list-sqlite-views.sql");
+
+        let mut stmt = self.conn.prepare(query)?;
+        let result = stmt
+            .query_map([], |row| {
+                trace!("{:?}", row);
+                Ok(ViewInfo {
+                    country: config.country.clone(),
+                    environment: config.environment.clone(),
+                    schema: config.schema.clone(),
+
+                    view_name: row.get("TABLE_NAME")?,
+                    columns: row.get("VIEW_COLUMNS")?,
+                    definition: row.get("VIEW_DEFINITION")?,
+                    // SQLite views are read only
+                    check_option: "NONE".to_string(),
+                    security_type: Option::None,
+                    updatable: false,
+
+                    display: Option::None,
+                })
+            })?
+            .collect::<rusqlite::Result<Vec<ViewInfo>>>()?;
+
+        Ok(result)
+    }
+
+    /// SQLite has no stored routines
+    fn collect_routines(&mut self) -> Result<Vec<RoutineInfo>> {
+        Ok(vec![])
+    }
+
+    /// SQLite has no scheduled events
+    fn collect_events(&mut self) -> Result<Vec<EventInfo>> {
+        Ok(vec![])
+    }
+}
+
+/// Timing, event and the rest of a `CREATE TRIGGER` statement after the table name, SQLite keeps
+/// only the statement. The event keeps the columns of `UPDATE OF`, and the rest keeps `FOR EACH ROW`
+/// and `WHEN` conditions.
+fn parse_trigger(sql: &str) -> (String, String, String) {
+    let upper = sql.to_ascii_uppercase();
+    let mut timing = "BEFORE";
+    // bounds of the event keyword
+    let mut event = Option::None;
+    let mut start = Option::None;
+    let mut i = 0;
+    while i <= upper.len() {
+        let c = upper[i..].chars().next().unwrap_or(' ');
+        if c.is_ascii_alphanumeric() || c == '_' {
+            start.get_or_insert(i);
+            i += 1;
+            continue;
+        }
+        if let Some(s) = start.take() {
+            match &upper[s..i] {
+                "AFTER" => timing = "AFTER",
+                "INSTEAD" => timing = "INSTEAD OF",
+                "DELETE" | "INSERT" | "UPDATE" if event.is_none() => event = Some((s, i)),
+                "ON" => {
+                    // keywords in upper case, column names as written
+                    let event = event
+                        .map(|(s, _)| &sql[s..i - 2])
+                        .unwrap_or_default()
+                        .split_whitespace()
+                        .enumerate()
+                        .map(|(n, word)| match n {
+                            0 => word.to_uppercase(),
+                            1 if word.eq_ignore_ascii_case("OF") => "OF".to_string(),
+                            _ => word.to_string(),
+                        })
+                        .collect::<Vec<String>>()
+                        .join(" ");
+                    let rest = sql[skip_name(sql, i)..].trim();
+                    return (timing.to_string(), event, rest.to_string());
+                }
+                _ => {}
+            }
+        }
+        // quoted names of the trigger or columns may hold keywords
+        i = match c {
+            '"' | '`' | '\'' => sql[i + 1..].find(c).map(|p| i + p + 2).unwrap_or(sql.len()),
+            '[' => sql[i..].find(']').map(|p| i + p + 1).unwrap_or(sql.len()),
+            _ => i + c.len_utf8(),
+        };
+    }
+    (timing.to_string(), String::new(), sql.to_string())
+}
+
+/// Index after a possibly quoted and qualified name following `start`
+fn skip_name(sql: &str, start: usize) -> usize {
+    let mut i = start + (sql.len() - start - sql[start..].trim_start().len());
+    loop {
+        let c = sql[i..].chars().next();
+        i = match c {
+            Some(q @ ('"' | '`')) => sql[i + 1..].find(q).map(|p| i + p + 2).unwrap_or(sql.len()),
+            Some('[') => sql[i..].find(']').map(|p| i + p + 1).unwrap_or(sql.len()),
+            _ => sql[i..]
+                .find(|c: char| !(c.is_alphanumeric() || c == '_'))
+                .map(|p| i + p)
+                .unwrap_or(sql.len()),
+        };
+        if !sql[i..].starts_with('.') {
+            return i;
+        }
+        i += 1;
+    }
+}
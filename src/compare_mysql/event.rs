@@ -0,0 +1,85 @@
+use log::debug;
+use std::collections::BTreeMap;
+
+use super::{
+    collector::{select_reference, write_csv},
+    diff::event_rows,
+    routine::normalize_definition,
+};
+use crate::value_object::{Deploy, Engine, EventInfo, ObjectKind, Result, UniqueEvent};
+
+pub fn output_event_info(
+    file: &str,
+    events: &[EventInfo],
+    deploys: &[Deploy],
+    delimiter: u8,
+    only_diff: bool,
+) -> Result<()> {
+    debug!("Generating csv for {} events", events.len());
+    write_csv(
+        file,
+        delimiter,
+        only_diff,
+        ObjectKind::Event,
+        deploys,
+        event_rows(events, deploys),
+    )
+}
+
+pub(crate) fn group_events(events: &[EventInfo]) -> BTreeMap<UniqueEvent<'_>, Vec<&EventInfo>> {
+    events.iter().fold(
+        BTreeMap::new(),
+        |mut acc: BTreeMap<UniqueEvent, Vec<&EventInfo>>, e| {
+            acc.entry(UniqueEvent {
+                schema: &e.schema,
+                event_name: &e.event_name,
+            })
+            .or_default()
+            .push(e);
+            acc
+        },
+    )
+}
+
+pub(crate) fn find_event<'a>(list: &[&'a EventInfo], deploy: &Deploy) -> Option<&'a EventInfo> {
+    list.iter()
+        .find(|e| e.environment == deploy.environment && e.country == deploy.country)
+        .copied()
+}
+
+/// Interval, time zone, completion and status on the first line, then the normalised body.
+///
+/// `STARTS` and `ENDS` of recurring events are left out, they hold the time each deploy was
+/// created at rather than its configuration.
+pub(crate) fn event_format(item: &EventInfo, engine: Engine) -> String {
+    let mut schedule = interval(&item.schedule).to_string();
+    if !item.time_zone.is_empty() {
+        schedule += format!(" TIME ZONE {}", item.time_zone).as_str();
+    }
+    format!(
+        "{} ON COMPLETION {} {}\n{}",
+        schedule,
+        item.on_completion,
+        item.status,
+        normalize_definition(&item.body, engine)
+    )
+}
+
+/// `EVERY <interval>` of a recurring schedule, one-time schedules as they are
+fn interval(schedule: &str) -> &str {
+    [" STARTS ", " ENDS "]
+        .iter()
+        .filter_map(|clause| schedule.find(clause))
+        .min()
+        .map(|index| &schedule[..index])
+        .unwrap_or(schedule)
+}
+
+pub(crate) fn find_most_used_event(deploys: &[Deploy], list: &[&EventInfo]) -> String {
+    let values = deploys
+        .iter()
+        .map(|deploy| find_event(list, deploy).map(|e| event_format(e, deploy.engine)))
+        .collect();
+
+    select_reference(deploys, values).unwrap_or_default()
+}
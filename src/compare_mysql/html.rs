@@ -0,0 +1,287 @@
+use log::info;
+use std::{
+    collections::{BTreeMap, BTreeSet},
+    io::Write,
+};
+
+use super::{
+    collector::{create_output, UNREACHABLE},
+    diff::{display_value, group_name, is_missing, reference_name},
+};
+use crate::value_object::{Deploy, DiffCell, DiffRow, ObjectKind, Result};
+
+const STYLE: &str = r#"
+body { font-family: -apple-system, "Segoe UI", Helvetica, Arial, sans-serif; margin: 2em; color: #222; }
+h1 { margin-bottom: 0.2em; }
+.meta { color: #666; margin-top: 0; }
+table { border-collapse: collapse; margin: 0.5em 0 1.5em; }
+th, td { border: 1px solid #ccc; padding: 4px 8px; text-align: left; vertical-align: top; font-size: 13px; }
+th { background: #f3f3f3; }
+td.value { font-family: Menlo, Consolas, monospace; white-space: pre-wrap; }
+td.outlier { background: #fbd5d5; }
+td.missing { background: #fde9c8; }
+td.unreachable { background: #e2e2e2; color: #777; }
+tr.identical td.value { color: #666; }
+.filters label { margin-right: 1em; }
+.filters div { margin: 0.3em 0; }
+section.table h3 { margin-bottom: 0.2em; }
+details summary { cursor: pointer; font-size: 1.2em; margin: 1em 0; }
+"#;
+
+const SCRIPT: &str = r#"
+function applyFilters() {
+  var checked = function (name) {
+    return Array.prototype.slice
+      .call(document.querySelectorAll('input[name="' + name + '"]:checked'))
+      .map(function (input) { return input.value; });
+  };
+  var environments = checked('environment');
+  var countries = checked('country');
+  document.querySelectorAll('[data-environment]').forEach(function (cell) {
+    var visible = environments.indexOf(cell.dataset.environment) >= 0
+      && countries.indexOf(cell.dataset.country) >= 0;
+    cell.style.display = visible ? '' : 'none';
+  });
+}
+"#;
+
+/// Write a self-contained HTML report of the comparison, with one section per table.
+///
+/// Tables whose objects are identical in all deploys are listed in a collapsed section, or only
+/// counted with `only_diff`.
+pub fn output_html(
+    file: &str,
+    deploys: &[Deploy],
+    rows: &[DiffRow],
+    only_diff: bool,
+) -> Result<()> {
+    info!("Generating html report to {}", file);
+    let mut writer = create_output(file)?;
+
+    // rows of each table, in the order of kinds then names
+    let mut tables: BTreeMap<(&str, &str), Vec<&DiffRow>> = BTreeMap::new();
+    for row in rows {
+        tables
+            .entry((&row.schema, &row.table))
+            .or_default()
+            .push(row);
+    }
+    for list in tables.values_mut() {
+        list.sort_by(|a, b| {
+            (a.kind, &a.name, &a.routine_type).cmp(&(b.kind, &b.name, &b.routine_type))
+        });
+    }
+    let (different, identical): (Vec<_>, Vec<_>) = tables
+        .iter()
+        .partition(|(_, list)| list.iter().any(|r| r.differs()));
+
+    writeln!(writer, "<!DOCTYPE html>")?;
+    writeln!(writer, "<html>\n<head>\n<meta charset=\"utf-8\">")?;
+    writeln!(writer, "<title>Schema comparison</title>")?;
+    writeln!(writer, "<style>{}</style>\n</head>\n<body>", STYLE)?;
+    writeln!(writer, "<h1>Schema comparison</h1>")?;
+    writeln!(
+        writer,
+        "<p class=\"meta\">Generated at {}, compared with {}</p>",
+        chrono::Local::now().format("%Y-%m-%d %H:%M:%S"),
+        escape(&reference_name(deploys))
+    )?;
+
+    write_summary(&mut writer, deploys, rows, different.len(), identical.len())?;
+    write_filters(&mut writer, deploys)?;
+
+    writeln!(writer, "<h2>Differences</h2>")?;
+    if different.is_empty() {
+        writeln!(writer, "<p>All tables are identical.</p>")?;
+    }
+    for ((schema, table), list) in &different {
+        write_table(&mut writer, deploys, schema, table, list)?;
+    }
+
+    if only_diff {
+        writeln!(
+            writer,
+            "<p>{} tables identical in all deploys omitted.</p>",
+            identical.len()
+        )?;
+    } else {
+        writeln!(
+            writer,
+            "<details>\n<summary>{} tables identical in all deploys</summary>",
+            identical.len()
+        )?;
+        for ((schema, table), list) in &identical {
+            write_table(&mut writer, deploys, schema, table, list)?;
+        }
+        writeln!(writer, "</details>")?;
+    }
+
+    writeln!(writer, "<script>{}</script>\n</body>\n</html>", SCRIPT)?;
+    writer.flush()?;
+    Ok(())
+}
+
+fn write_summary(
+    writer: &mut impl Write,
+    deploys: &[Deploy],
+    rows: &[DiffRow],
+    different_tables: usize,
+    identical_tables: usize,
+) -> Result<()> {
+    writeln!(writer, "<h2>Summary</h2>")?;
+    writeln!(
+        writer,
+        "<p>{} tables differ, {} tables are identical.</p>",
+        different_tables, identical_tables
+    )?;
+
+    writeln!(writer, "<table class=\"summary\">")?;
+    writeln!(
+        writer,
+        "<tr><th>Object</th><th>Compared</th><th>Identical</th><th>Different</th></tr>"
+    )?;
+    for kind in ObjectKind::ALL {
+        let total = rows.iter().filter(|r| r.kind == kind).count();
+        let different = rows
+            .iter()
+            .filter(|r| r.kind == kind && r.differs())
+            .count();
+        writeln!(
+            writer,
+            "<tr><td>{}</td><td>{}</td><td>{}</td><td>{}</td></tr>",
+            kind.label(),
+            total,
+            total - different,
+            different
+        )?;
+    }
+    writeln!(writer, "</table>")?;
+
+    writeln!(writer, "<table class=\"summary\">")?;
+    writeln!(writer, "<tr><th>Deploy</th><th>Different objects</th></tr>")?;
+    for (index, deploy) in deploys.iter().enumerate() {
+        let count = if deploy.unreachable {
+            UNREACHABLE.to_string()
+        } else {
+            rows.iter()
+                .filter(|r| r.cells[index].differs)
+                .count()
+                .to_string()
+        };
+        writeln!(
+            writer,
+            "<tr><td>{}</td><td>{}</td></tr>",
+            escape(&deploy_name(deploy)),
+            count
+        )?;
+    }
+    writeln!(writer, "</table>")?;
+    Ok(())
+}
+
+fn write_filters(writer: &mut impl Write, deploys: &[Deploy]) -> Result<()> {
+    let environments: BTreeSet<&str> = deploys.iter().map(|d| d.environment).collect();
+    let countries: BTreeSet<&str> = deploys.iter().map(|d| d.country).collect();
+
+    writeln!(writer, "<div class=\"filters\">")?;
+    for (name, values) in [("environment", environments), ("country", countries)] {
+        write!(writer, "<div>{}: ", name)?;
+        for value in values {
+            write!(
+                writer,
+                "<label><input type=\"checkbox\" name=\"{}\" value=\"{}\" checked \
+                 onchange=\"applyFilters()\"> {}</label>",
+                name,
+                escape(value),
+                escape(value)
+            )?;
+        }
+        writeln!(writer, "</div>")?;
+    }
+    writeln!(writer, "</div>")?;
+    Ok(())
+}
+
+fn write_table(
+    writer: &mut impl Write,
+    deploys: &[Deploy],
+    schema: &str,
+    table: &str,
+    rows: &[&DiffRow],
+) -> Result<()> {
+    writeln!(writer, "<section class=\"table\">")?;
+    writeln!(writer, "<h3>{}</h3>", escape(&group_name(schema, table)))?;
+    writeln!(writer, "<table>")?;
+
+    write!(writer, "<tr><th>Object</th><th>Name</th>")?;
+    for deploy in deploys {
+        write!(
+            writer,
+            "<th {}>{}{}</th>",
+            deploy_data(deploy),
+            escape(&deploy_name(deploy)),
+            if deploy.baseline { " (baseline)" } else { "" }
+        )?;
+    }
+    writeln!(writer, "</tr>")?;
+
+    for row in rows {
+        write!(
+            writer,
+            "<tr class=\"{}\"><td>{}</td><td>{}</td>",
+            if row.differs() {
+                "different"
+            } else {
+                "identical"
+            },
+            row.kind.label(),
+            escape(&row.display_name())
+        )?;
+        for (deploy, cell) in deploys.iter().zip(&row.cells) {
+            write!(
+                writer,
+                "<td class=\"value {}\" {}>{}</td>",
+                cell_class(cell),
+                deploy_data(deploy),
+                escape(&display_value(cell))
+            )?;
+        }
+        writeln!(writer, "</tr>")?;
+    }
+
+    writeln!(writer, "</table>\n</section>")?;
+    Ok(())
+}
+
+fn cell_class(cell: &DiffCell) -> &'static str {
+    if cell.unreachable {
+        "unreachable"
+    } else if cell.differs && is_missing(cell) {
+        "missing"
+    } else if cell.differs {
+        "outlier"
+    } else {
+        ""
+    }
+}
+
+fn deploy_name(deploy: &Deploy) -> String {
+    format!("[{} {}]", deploy.environment, deploy.country)
+}
+
+fn deploy_data(deploy: &Deploy) -> String {
+    format!(
+        "data-environment=\"{}\" data-country=\"{}\"",
+        escape(deploy.environment),
+        escape(deploy.country)
+    )
+}
+
+fn escape(value: &str) -> String {
+    value
+        .replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+        .replace('\'', "&#39;")
+}
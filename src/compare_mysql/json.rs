@@ -0,0 +1,111 @@
+use log::info;
+use serde_json::{json, Map, Value};
+use std::io::Write;
+
+use super::collector::{create_output, MISSING};
+use crate::value_object::{Deploy, DiffRow, ObjectKind, Result};
+
+/// Write differing objects as one JSON document, along with the compared deploys
+pub fn output_json(file: &str, deploys: &[Deploy], rows: &[DiffRow]) -> Result<()> {
+    info!("Generating json diff to {}", file);
+    let mut writer = create_output(file)?;
+
+    let differences: Vec<Value> = rows
+        .iter()
+        .filter(|r| r.differs())
+        .map(|r| diff_object(deploys, r))
+        .collect();
+    let document = json!({
+        "deploys": deploys
+            .iter()
+            .map(|d| json!({
+                "environment": d.environment,
+                "country": d.country,
+                "baseline": d.baseline,
+                "unreachable": d.unreachable,
+            }))
+            .collect::<Vec<Value>>(),
+        "differences": differences,
+    });
+
+    serde_json::to_writer_pretty(&mut writer, &document)?;
+    writer.flush()?;
+    Ok(())
+}
+
+/// Write differing objects as JSON Lines, one object per line
+pub fn output_json_lines(file: &str, deploys: &[Deploy], rows: &[DiffRow]) -> Result<()> {
+    info!("Generating json lines diff to {}", file);
+    let mut writer = create_output(file)?;
+
+    for row in rows.iter().filter(|r| r.differs()) {
+        serde_json::to_writer(&mut writer, &diff_object(deploys, row))?;
+        writeln!(writer)?;
+    }
+    writer.flush()?;
+    Ok(())
+}
+
+/// Key, reference definition and the value of each deploy, `null` when the object is absent
+fn diff_object(deploys: &[Deploy], row: &DiffRow) -> Value {
+    let mut key = Map::new();
+    key.insert("schema".to_string(), json!(row.schema));
+    if row.kind.in_table() {
+        key.insert("table".to_string(), json!(row.table));
+    }
+    match row.kind {
+        ObjectKind::Table | ObjectKind::Partition => {}
+        ObjectKind::Column => {
+            key.insert("column".to_string(), json!(row.name));
+        }
+        ObjectKind::Index => {
+            key.insert("index".to_string(), json!(row.name));
+        }
+        ObjectKind::ForeignKey => {
+            key.insert("foreign_key".to_string(), json!(row.name));
+        }
+        ObjectKind::Trigger => {
+            key.insert("trigger".to_string(), json!(row.name));
+        }
+        ObjectKind::View => {
+            key.insert("view".to_string(), json!(row.name));
+        }
+        ObjectKind::Routine => {
+            key.insert("routine".to_string(), json!(row.name));
+            key.insert("routine_type".to_string(), json!(row.routine_type));
+        }
+        ObjectKind::Event => {
+            key.insert("event".to_string(), json!(row.name));
+        }
+    }
+
+    let values: Vec<Value> = deploys
+        .iter()
+        .zip(&row.cells)
+        .map(|(deploy, cell)| {
+            json!({
+                "environment": deploy.environment,
+                "country": deploy.country,
+                "value": if cell.unreachable { Value::Null } else { definition(row.kind, &cell.value) },
+                "differs": cell.differs,
+                "unreachable": cell.unreachable,
+            })
+        })
+        .collect();
+
+    json!({
+        "kind": row.kind,
+        "key": key,
+        "reference": definition(row.kind, &row.reference),
+        "deploys": values,
+    })
+}
+
+fn definition(kind: ObjectKind, value: &str) -> Value {
+    match kind {
+        ObjectKind::Table if value == MISSING => Value::Null,
+        ObjectKind::Table => json!(value),
+        _ if value.is_empty() => Value::Null,
+        _ => json!(value),
+    }
+}
@@ -0,0 +1,107 @@
+use log::info;
+use std::{collections::BTreeMap, io::Write};
+
+use super::{
+    collector::create_output,
+    diff::{display_value, group_name, reference_name},
+};
+use crate::value_object::{Deploy, DiffRow, ObjectKind, Result};
+
+/// Write a JUnit XML report with one test suite per table and one test case per object.
+///
+/// A test case fails when any deploy differs from the reference definition, the failure lists the
+/// differing deploys.
+pub fn output_junit(file: &str, deploys: &[Deploy], rows: &[DiffRow]) -> Result<()> {
+    info!("Generating junit report to {}", file);
+    let mut writer = create_output(file)?;
+
+    let mut tables: BTreeMap<(&str, &str), Vec<&DiffRow>> = BTreeMap::new();
+    for row in rows {
+        tables
+            .entry((&row.schema, &row.table))
+            .or_default()
+            .push(row);
+    }
+    for list in tables.values_mut() {
+        list.sort_by(|a, b| {
+            (a.kind, &a.name, &a.routine_type).cmp(&(b.kind, &b.name, &b.routine_type))
+        });
+    }
+    let reference = reference_name(deploys);
+
+    writeln!(writer, "<?xml version=\"1.0\" encoding=\"UTF-8\"?>")?;
+    writeln!(
+        writer,
+        "<testsuites name=\"scomp\" tests=\"{}\" failures=\"{}\">",
+        rows.len(),
+        rows.iter().filter(|r| r.differs()).count()
+    )?;
+    for ((schema, table), list) in &tables {
+        let suite = group_name(schema, table);
+        writeln!(
+            writer,
+            "  <testsuite name=\"{}\" tests=\"{}\" failures=\"{}\">",
+            escape(&suite),
+            list.len(),
+            list.iter().filter(|r| r.differs()).count()
+        )?;
+        for row in list {
+            let name = match row.kind {
+                ObjectKind::Table => "Table options".to_string(),
+                kind if !kind.named() => kind.label().to_string(),
+                kind => format!("{} {}", kind.label(), row.display_name()),
+            };
+            write!(
+                writer,
+                "    <testcase classname=\"{}\" name=\"{}\"",
+                escape(&suite),
+                escape(&name)
+            )?;
+            if !row.differs() {
+                writeln!(writer, "/>")?;
+                continue;
+            }
+
+            let outliers: Vec<String> = deploys
+                .iter()
+                .zip(&row.cells)
+                .filter(|(_, cell)| cell.differs)
+                .map(|(deploy, cell)| {
+                    format!(
+                        "[{} {}] {}",
+                        deploy.environment,
+                        deploy.country,
+                        display_value(cell)
+                    )
+                })
+                .collect();
+            writeln!(writer, ">")?;
+            writeln!(
+                writer,
+                "      <failure type=\"drift\" message=\"{} deploys differ from {}\">Reference: {}\n{}</failure>",
+                outliers.len(),
+                escape(&reference),
+                if row.reference.is_empty() {
+                    "none".to_string()
+                } else {
+                    escape(&row.reference)
+                },
+                escape(&outliers.join("\n"))
+            )?;
+            writeln!(writer, "    </testcase>")?;
+        }
+        writeln!(writer, "  </testsuite>")?;
+    }
+    writeln!(writer, "</testsuites>")?;
+    writer.flush()?;
+    Ok(())
+}
+
+fn escape(value: &str) -> String {
+    value
+        .replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+        .replace('\'', "&apos;")
+}
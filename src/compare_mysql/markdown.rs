@@ -0,0 +1,175 @@
+use log::{info, warn};
+use std::{
+    collections::{BTreeMap, BTreeSet},
+    io::Write,
+};
+
+use super::{
+    collector::create_output,
+    diff::{display_value, group_name, reference_name},
+};
+use crate::value_object::{Deploy, DiffRow, ObjectKind, Result};
+
+/// Room kept for the truncation note when the report is capped
+const TRUNCATION_NOTE_SIZE: usize = 160;
+
+/// Write a Markdown report of tables with differences, to be pasted into merge requests.
+///
+/// Each differing object gets a table of deploys grouped by definition. With `max_size`, objects
+/// which don't fit in the size in bytes are left out and counted in a note at the end, sizes too
+/// small for the title, the summary and the note are rejected.
+pub fn output_markdown(
+    file: &str,
+    deploys: &[Deploy],
+    rows: &[DiffRow],
+    max_size: Option<usize>,
+) -> Result<()> {
+    info!("Generating markdown report to {}", file);
+
+    let mut tables: BTreeMap<(&str, &str), Vec<&DiffRow>> = BTreeMap::new();
+    for row in rows.iter().filter(|r| r.differs()) {
+        tables
+            .entry((&row.schema, &row.table))
+            .or_default()
+            .push(row);
+    }
+    for list in tables.values_mut() {
+        list.sort_by(|a, b| {
+            (a.kind, &a.name, &a.routine_type).cmp(&(b.kind, &b.name, &b.routine_type))
+        });
+    }
+    let table_count = rows
+        .iter()
+        .filter(|r| r.kind.in_table())
+        .map(|r| (&r.schema, &r.table))
+        .collect::<BTreeSet<_>>()
+        .len();
+    let differing_tables = tables.keys().filter(|(_, table)| !table.is_empty()).count();
+
+    let mut output = String::new();
+    output += "# Schema comparison\n\n";
+    output += format!(
+        "{} of {} tables differ from {}.\n",
+        differing_tables,
+        table_count,
+        reference_name(deploys)
+    )
+    .as_str();
+
+    let limit = match max_size {
+        Some(size) if size < output.len() + TRUNCATION_NOTE_SIZE => {
+            return Err(format!(
+                "Markdown report can't be capped at {} bytes, at least {} are needed",
+                size,
+                output.len() + TRUNCATION_NOTE_SIZE
+            )
+            .into())
+        }
+        Some(size) => Some(size - TRUNCATION_NOTE_SIZE),
+        None => None,
+    };
+    let mut omitted = 0;
+    for ((schema, table), list) in &tables {
+        let mut heading = Some(format!("\n## {}\n", group_name(schema, table)));
+        for row in list {
+            let section = object_section(deploys, row);
+            let size = heading.as_ref().map(|h| h.len()).unwrap_or(0) + section.len();
+            if omitted > 0 || limit.map(|l| output.len() + size > l).unwrap_or(false) {
+                omitted += 1;
+                continue;
+            }
+            if let Some(heading) = heading.take() {
+                output += heading.as_str();
+            }
+            output += section.as_str();
+        }
+    }
+
+    if omitted > 0 {
+        warn!(
+            "Markdown report is capped at {} bytes, {} objects are left out",
+            max_size.unwrap_or_default(),
+            omitted
+        );
+        output += format!(
+            "\n_{} more differing objects are left out to keep the report under {} bytes._\n",
+            omitted,
+            max_size.unwrap_or_default()
+        )
+        .as_str();
+    }
+
+    let mut writer = create_output(file)?;
+    writer.write_all(output.as_bytes())?;
+    writer.flush()?;
+    Ok(())
+}
+
+/// Deploys grouped by definition, the reference definition first
+fn object_section(deploys: &[Deploy], row: &DiffRow) -> String {
+    let mut groups: Vec<(String, Vec<&Deploy>)> = vec![];
+    for (deploy, cell) in deploys.iter().zip(&row.cells) {
+        let value = display_value(cell);
+        match groups.iter_mut().find(|(v, _)| *v == value) {
+            Some((_, list)) => list.push(deploy),
+            None => groups.push((value, vec![deploy])),
+        }
+    }
+    groups.sort_by_key(|(value, _)| *value != row.reference);
+
+    let mut section = String::new();
+    if row.kind == ObjectKind::Table {
+        section += "\n**Table options**\n\n";
+    } else if !row.kind.named() {
+        section += format!("\n**{}**\n\n", row.kind.label()).as_str();
+    } else if !row.routine_type.is_empty() {
+        section += format!(
+            "\n**{} `{}` ({})**\n\n",
+            row.kind.label(),
+            row.name,
+            row.routine_type
+        )
+        .as_str();
+    } else {
+        section += format!("\n**{} `{}`**\n\n", row.kind.label(), row.name).as_str();
+    }
+    section += "| Deploys | Definition |\n| --- | --- |\n";
+    for (value, list) in groups {
+        let names = list
+            .iter()
+            .map(|d| format!("{} {}", d.environment, d.country))
+            .collect::<Vec<String>>()
+            .join(", ");
+        let definition = if value.is_empty() {
+            "_none_".to_string()
+        } else {
+            code_span(&escape(&value))
+        };
+        let reference = if value == row.reference {
+            " (reference)"
+        } else {
+            ""
+        };
+        section += format!("| {} | {}{} |\n", escape(&names), definition, reference).as_str();
+    }
+    section
+}
+
+fn escape(value: &str) -> String {
+    value.replace('|', "\\|").replace('\n', " ")
+}
+
+/// Inline code fenced by more backticks than the longest run in `value`, so its backticks are kept
+fn code_span(value: &str) -> String {
+    let longest = value
+        .split(|c| c != '`')
+        .map(|run| run.len())
+        .max()
+        .unwrap_or(0);
+    let fence = "`".repeat(longest + 1);
+    if value.starts_with('`') || value.ends_with('`') {
+        format!("{} {} {}", fence, value, fence)
+    } else {
+        format!("{}{}{}", fence, value, fence)
+    }
+}
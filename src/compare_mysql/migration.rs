@@ -0,0 +1,463 @@
+use log::{debug, info};
+use std::{
+    collections::{BTreeMap, BTreeSet},
+    fs,
+    path::Path,
+};
+
+use super::collector::{
+    column_format, find_foreign_key_columns, find_index_columns, find_most_used_column,
+    find_most_used_foreign_key, find_most_used_index, foreign_key_format, group_columns,
+    group_foreign_keys, group_indices, index_format,
+};
+use super::diff::reference_name;
+use crate::value_object::{
+    ColumnInfo, ColumnRules, Deploy, Engine, ForeignKeyInfo, IndexInfo, Result,
+};
+
+/// Write one migration script per deploy into `dir`, named `<environment>-<country>.sql`, except
+/// for the baseline. Scripts are written in the MySQL dialect, other engines are rejected.
+pub fn output_migration(
+    dir: &str,
+    columns: &[ColumnInfo],
+    indices: &[IndexInfo],
+    foreign_keys: &[ForeignKeyInfo],
+    deploys: &[Deploy],
+    rules: &ColumnRules,
+) -> Result<()> {
+    if let Some(deploy) = deploys.iter().find(|d| d.engine != Engine::Mysql) {
+        return Err(format!(
+            "Migration scripts are only written for MySQL, [{} {}] is a {} deploy",
+            deploy.environment,
+            deploy.country,
+            deploy.engine.scheme()
+        )
+        .into());
+    }
+    fs::create_dir_all(dir)?;
+
+    let target = reference_name(deploys);
+
+    for deploy in deploys.iter().filter(|d| !d.unreachable && !d.baseline) {
+        let statements = build_migration(columns, indices, foreign_keys, deploys, deploy, rules);
+        let file = Path::new(dir).join(format!("{}-{}.sql", deploy.environment, deploy.country));
+        info!(
+            "Generating {} statements for [{} {}] to {}",
+            statements.len(),
+            deploy.environment,
+            deploy.country,
+            file.display()
+        );
+
+        let mut output = format!(
+            "-- Migrate [{} {}] to {}\n",
+            deploy.environment, deploy.country, target
+        );
+        for statement in statements {
+            output += statement.as_str();
+            output += "\n";
+        }
+        fs::write(file, output)?;
+    }
+
+    Ok(())
+}
+
+/// Build the statements which converge a deploy to the baseline, or the most used definition, of
+/// every column, index and foreign key, columns are compared according to `rules`.
+///
+/// Statements are ordered so each one stays valid after the previous ones ran: foreign keys which
+/// differ, or use a column or an index about to change, are dropped first, then indices are
+/// dropped, columns are dropped, modified and added, indices are created and foreign keys are
+/// added last. Tables which only exist in the deploy are reported as `-- manual:` comments and
+/// never dropped, so are generated columns, whose expression isn't collected.
+pub fn build_migration(
+    columns: &[ColumnInfo],
+    indices: &[IndexInfo],
+    foreign_keys: &[ForeignKeyInfo],
+    deploys: &[Deploy],
+    deploy: &Deploy,
+    rules: &ColumnRules,
+) -> Vec<String> {
+    debug!(
+        "Building migration for [{} {}]",
+        deploy.environment, deploy.country
+    );
+
+    // (exists in deploy, exists in most used definition) of each table
+    let mut tables: BTreeMap<&str, (bool, bool)> = BTreeMap::new();
+    let mut missing_columns: Vec<&ColumnInfo> = vec![];
+    let mut extra_columns: Vec<&ColumnInfo> = vec![];
+    let mut changed_columns: Vec<&ColumnInfo> = vec![];
+
+    for (key, list) in group_columns(columns) {
+        let most_used = find_most_used_column(deploys, &list, rules);
+        let expected = list
+            .iter()
+            .find(|c| !most_used.is_empty() && column_format(c, rules) == most_used);
+        let actual = list
+            .iter()
+            .find(|c| c.environment == deploy.environment && c.country == deploy.country);
+
+        let table = tables.entry(key.table).or_default();
+        table.0 |= actual.is_some();
+        table.1 |= expected.is_some();
+
+        match (actual, expected) {
+            (None, Some(expected)) => missing_columns.push(expected),
+            (Some(actual), None) => extra_columns.push(actual),
+            (Some(actual), Some(expected)) if column_format(actual, rules) != most_used => {
+                changed_columns.push(expected)
+            }
+            _ => {}
+        }
+    }
+
+    // keep the ordinal order of collected columns
+    let position = |c: &&ColumnInfo| columns.iter().position(|i| std::ptr::eq(i, *c));
+    missing_columns.sort_by_key(position);
+    extra_columns.sort_by_key(position);
+    changed_columns.sort_by_key(position);
+
+    let kept = |table: &str| tables.get(table).map(|t| t.1).unwrap_or(true);
+    let created = |table: &str| tables.get(table).map(|t| !t.0).unwrap_or(false);
+
+    let mut dropped_indices: Vec<Vec<&IndexInfo>> = vec![];
+    let mut create_indices: Vec<String> = vec![];
+
+    for (key, list) in group_indices(indices) {
+        if !kept(key.table) {
+            continue;
+        }
+
+        let most_used = find_most_used_index(deploys, &list);
+        let actual = find_index_columns(&list, deploy);
+        let actual_format = if actual.is_empty() {
+            String::new()
+        } else {
+            index_format(&actual)
+        };
+        if actual_format == most_used {
+            continue;
+        }
+
+        if !actual.is_empty() {
+            dropped_indices.push(actual);
+        }
+        if let Some(expected) = deploys
+            .iter()
+            .map(|d| find_index_columns(&list, d))
+            .find(|c| !c.is_empty() && index_format(c) == most_used)
+        {
+            create_indices.push(create_index(&expected));
+        }
+    }
+
+    // columns whose foreign keys have to be dropped before they change
+    let altered: BTreeSet<(&str, &str)> = extra_columns
+        .iter()
+        .chain(&changed_columns)
+        .map(|c| (c.table.as_str(), c.column_name.as_str()))
+        .collect();
+
+    let mut drop_foreign_keys: Vec<String> = vec![];
+    let mut add_foreign_keys: Vec<String> = vec![];
+
+    for (key, list) in group_foreign_keys(foreign_keys) {
+        if !kept(key.table) {
+            continue;
+        }
+
+        let most_used = find_most_used_foreign_key(deploys, &list);
+        let actual = find_foreign_key_columns(&list, deploy);
+        let changed = if actual.is_empty() {
+            !most_used.is_empty()
+        } else {
+            foreign_key_format(&actual) != most_used
+        };
+        let affected = !actual.is_empty()
+            && (uses_altered(&actual, &altered) || backed_by(&actual, &dropped_indices));
+        if !changed && !affected {
+            continue;
+        }
+
+        if !actual.is_empty() {
+            drop_foreign_keys.push(drop_foreign_key(&actual));
+        }
+        if let Some(expected) = deploys
+            .iter()
+            .map(|d| find_foreign_key_columns(&list, d))
+            .find(|c| !c.is_empty() && foreign_key_format(c) == most_used)
+        {
+            add_foreign_keys.push(add_foreign_key(&expected));
+        }
+    }
+
+    let mut statements = drop_foreign_keys;
+    statements.extend(dropped_indices.iter().map(|columns| drop_index(columns)));
+
+    let mut dropped_tables: BTreeSet<&str> = BTreeSet::new();
+    for column in extra_columns {
+        if kept(&column.table) {
+            statements.push(format!(
+                "ALTER TABLE {} DROP COLUMN {};",
+                quote(&column.table),
+                quote(&column.column_name)
+            ));
+        } else if dropped_tables.insert(&column.table) {
+            statements.push(format!(
+                "-- manual: drop table {} if needed, it isn't in the reference definition",
+                quote(&column.table)
+            ));
+        }
+    }
+
+    for column in changed_columns {
+        if generated(column) {
+            statements.push(manual(column, "modify"));
+            continue;
+        }
+        statements.push(format!(
+            "ALTER TABLE {} MODIFY COLUMN {} {};",
+            quote(&column.table),
+            quote(&column.column_name),
+            column_definition(column)
+        ));
+    }
+
+    let mut new_tables: BTreeMap<&str, Vec<&ColumnInfo>> = BTreeMap::new();
+    for column in missing_columns {
+        if generated(column) {
+            statements.push(manual(column, "add"));
+        } else if created(&column.table) {
+            new_tables.entry(&column.table).or_default().push(column);
+        } else {
+            statements.push(format!(
+                "ALTER TABLE {} ADD COLUMN {} {};",
+                quote(&column.table),
+                quote(&column.column_name),
+                column_definition(column)
+            ));
+        }
+    }
+    for (table, list) in new_tables {
+        let definitions = list
+            .iter()
+            .map(|c| format!("  {} {}", quote(&c.column_name), column_definition(c)))
+            .collect::<Vec<String>>()
+            .join(",\n");
+        statements.push(format!(
+            "CREATE TABLE {} (\n{}\n);",
+            quote(table),
+            definitions
+        ));
+    }
+
+    statements.append(&mut create_indices);
+    statements.append(&mut add_foreign_keys);
+    statements
+}
+
+/// The foreign key uses or references a column which is dropped or modified
+fn uses_altered(columns: &[&ForeignKeyInfo], altered: &BTreeSet<(&str, &str)>) -> bool {
+    columns.iter().any(|c| {
+        altered.contains(&(c.table.as_str(), c.column.as_str()))
+            || altered.contains(&(c.referenced_table.as_str(), c.referenced_column.as_str()))
+    })
+}
+
+/// One of the indices is the one MySQL keeps for the foreign key, on the constraint columns or on
+/// the referenced columns, which it refuses to drop while the constraint exists
+fn backed_by(columns: &[&ForeignKeyInfo], indices: &[Vec<&IndexInfo>]) -> bool {
+    let prefix = |index: &[&IndexInfo], table: &str, names: Vec<&str>| {
+        index[0].table == table
+            && index.len() >= names.len()
+            && index.iter().zip(&names).all(|(i, name)| i.column == *name)
+    };
+    let first = columns[0];
+    indices.iter().any(|index| {
+        prefix(
+            index,
+            &first.table,
+            columns.iter().map(|c| c.column.as_str()).collect(),
+        ) || prefix(
+            index,
+            &first.referenced_table,
+            columns
+                .iter()
+                .map(|c| c.referenced_column.as_str())
+                .collect(),
+        )
+    })
+}
+
+fn column_definition(column: &ColumnInfo) -> String {
+    let mut definition = column.column_type.clone();
+    if let Some(charset) = &column.charset {
+        definition += format!(" CHARACTER SET {}", charset).as_str();
+    }
+    if let Some(collation) = &column.collation {
+        definition += format!(" COLLATE {}", collation).as_str();
+    }
+    if column.nullable == "YES" {
+        definition += " NULL";
+    } else {
+        definition += " NOT NULL";
+    }
+    if let Some(value) = &column.default_value {
+        definition += " DEFAULT ";
+        definition += default_literal(value).as_str();
+    }
+    if let Some(extra) = column.extra.as_ref().and_then(|e| safe_extra(e)) {
+        definition += " ";
+        definition += extra.as_str();
+    }
+    if let Some(comment) = column.comment.as_ref().filter(|c| !c.is_empty()) {
+        definition += format!(" COMMENT '{}'", escape(comment)).as_str();
+    }
+    definition
+}
+
+/// Attributes of `EXTRA` which are valid in a column definition, others such as
+/// `DEFAULT_GENERATED` or `INVISIBLE` are left out
+fn safe_extra(extra: &str) -> Option<String> {
+    let mut parts = vec![];
+    let lower = extra.to_lowercase();
+    if lower.contains("auto_increment") {
+        parts.push("AUTO_INCREMENT".to_string());
+    }
+    if let Some(index) = lower.find("on update ") {
+        if let Some(value) = extra[index + "on update ".len()..]
+            .split_whitespace()
+            .next()
+        {
+            parts.push(format!("ON UPDATE {}", value));
+        }
+    }
+    if parts.is_empty() {
+        Option::None
+    } else {
+        Some(parts.join(" "))
+    }
+}
+
+/// Virtual and stored generated columns, `DEFAULT_GENERATED` only marks an expression default
+fn generated(column: &ColumnInfo) -> bool {
+    column
+        .extra
+        .as_ref()
+        .map(|e| e.to_uppercase())
+        .filter(|e| e.contains("VIRTUAL GENERATED") || e.contains("STORED GENERATED"))
+        .is_some()
+}
+
+fn manual(column: &ColumnInfo, action: &str) -> String {
+    format!(
+        "-- manual: {} generated column {}.{} ({}) with its expression",
+        action,
+        quote(&column.table),
+        quote(&column.column_name),
+        column.column_type
+    )
+}
+
+/// information_schema keeps string defaults unquoted, while numbers and expressions are raw
+fn default_literal(value: &str) -> String {
+    let upper = value.to_uppercase();
+    if value.parse::<f64>().is_ok()
+        || upper == "NULL"
+        || upper.starts_with("CURRENT_TIMESTAMP")
+        || value.starts_with('(')
+        || value.starts_with("b'")
+    {
+        value.to_string()
+    } else {
+        format!("'{}'", escape(value))
+    }
+}
+
+fn drop_index(columns: &[&IndexInfo]) -> String {
+    let index = columns[0];
+    if index.index_name == "PRIMARY" {
+        format!("ALTER TABLE {} DROP PRIMARY KEY;", quote(&index.table))
+    } else {
+        format!(
+            "DROP INDEX {} ON {};",
+            quote(&index.index_name),
+            quote(&index.table)
+        )
+    }
+}
+
+fn create_index(columns: &[&IndexInfo]) -> String {
+    let index = columns[0];
+    let names = columns
+        .iter()
+        .map(|c| quote(&c.column))
+        .collect::<Vec<String>>()
+        .join(", ");
+    if index.index_name == "PRIMARY" {
+        format!(
+            "ALTER TABLE {} ADD PRIMARY KEY ({});",
+            quote(&index.table),
+            names
+        )
+    } else {
+        format!(
+            "CREATE {}INDEX {} ON {} ({});",
+            if index.unique { "UNIQUE " } else { "" },
+            quote(&index.index_name),
+            quote(&index.table),
+            names
+        )
+    }
+}
+
+fn drop_foreign_key(columns: &[&ForeignKeyInfo]) -> String {
+    let foreign_key = columns[0];
+    format!(
+        "ALTER TABLE {} DROP FOREIGN KEY {};",
+        quote(&foreign_key.table),
+        quote(&foreign_key.constraint_name)
+    )
+}
+
+fn add_foreign_key(columns: &[&ForeignKeyInfo]) -> String {
+    let foreign_key = columns[0];
+    let referenced_table = if foreign_key.referenced_schema.is_empty()
+        || foreign_key.referenced_schema == foreign_key.schema
+    {
+        quote(&foreign_key.referenced_table)
+    } else {
+        format!(
+            "{}.{}",
+            quote(&foreign_key.referenced_schema),
+            quote(&foreign_key.referenced_table)
+        )
+    };
+    let names = |name: fn(&ForeignKeyInfo) -> &str| {
+        columns
+            .iter()
+            .map(|c| quote(name(c)))
+            .collect::<Vec<String>>()
+            .join(", ")
+    };
+    format!(
+        "ALTER TABLE {} ADD CONSTRAINT {} FOREIGN KEY ({}) REFERENCES {} ({}) ON UPDATE {} ON DELETE {};",
+        quote(&foreign_key.table),
+        quote(&foreign_key.constraint_name),
+        names(|c| &c.column),
+        referenced_table,
+        names(|c| &c.referenced_column),
+        foreign_key.update_rule,
+        foreign_key.delete_rule
+    )
+}
+
+fn quote(name: &str) -> String {
+    format!("`{}`", name.replace('`', "``"))
+}
+
+fn escape(value: &str) -> String {
+    value.replace('\\', "\\\\").replace('\'', "''")
+}
@@ -1,3 +1,38 @@
+mod check;
 mod collector;
+mod diff;
+mod engine;
+mod event;
+mod html;
+mod json;
+mod junit;
+mod markdown;
+mod migration;
+mod partition;
+mod report;
+mod routine;
+mod snapshot;
+mod trigger;
+mod view;
+mod xlsx;
 
-pub use collector::{build_deploy, output_column_info, output_index_info, read_config, start};
+pub use check::find_drift;
+pub use collector::{
+    build_deploy, collect, compare, output_column_info, output_foreign_key_info, output_index_info,
+    output_missing_tables, output_table_info, read_config, read_rules, set_baseline, start,
+    strip_default_collation, MISSING, UNREACHABLE,
+};
+pub use engine::{connect, MysqlCollector, PostgresCollector, SchemaCollector, SqliteCollector};
+pub use event::output_event_info;
+pub use html::output_html;
+pub use json::{output_json, output_json_lines};
+pub use junit::output_junit;
+pub use markdown::output_markdown;
+pub use migration::{build_migration, output_migration};
+pub use partition::{output_partition_info, NOT_PARTITIONED};
+pub use report::{load_diff, report, save_diff, DIFF_VERSION};
+pub use routine::{normalize_definition, output_routine_diff, output_routine_info};
+pub use snapshot::{load_snapshot, save_snapshot, SNAPSHOT_VERSION};
+pub use trigger::output_trigger_info;
+pub use view::{normalize_view_definition, output_view_info};
+pub use xlsx::output_xlsx;
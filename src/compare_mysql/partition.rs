@@ -0,0 +1,143 @@
+use log::debug;
+use std::collections::BTreeMap;
+
+use super::{
+    collector::{select_reference, write_csv},
+    diff::partition_rows,
+};
+use crate::value_object::{Deploy, ObjectKind, PartitionInfo, Result, TableInfo, UniqueTable};
+
+/// Cell value of tables which exist in the deploy without partitions
+pub const NOT_PARTITIONED: &str = "NOT PARTITIONED";
+
+pub fn output_partition_info(
+    file: &str,
+    partitions: &[PartitionInfo],
+    tables: &[TableInfo],
+    deploys: &[Deploy],
+    ignore_boundaries: bool,
+    delimiter: u8,
+    only_diff: bool,
+) -> Result<()> {
+    debug!("Generating csv for {} partitions", partitions.len());
+    write_csv(
+        file,
+        delimiter,
+        only_diff,
+        ObjectKind::Partition,
+        deploys,
+        partition_rows(partitions, tables, deploys, ignore_boundaries),
+    )
+}
+
+pub(crate) fn group_partitions(
+    partitions: &[PartitionInfo],
+) -> BTreeMap<UniqueTable<'_>, Vec<&PartitionInfo>> {
+    partitions.iter().fold(BTreeMap::new(), |mut acc, p| {
+        acc.entry(UniqueTable {
+            schema: &p.schema,
+            table: &p.table,
+        })
+        .or_default()
+        .push(p);
+        acc
+    })
+}
+
+/// Partitions of a table in the given deploy, ordered by position
+pub(crate) fn find_partitions<'a>(
+    list: &[&'a PartitionInfo],
+    deploy: &Deploy,
+) -> Vec<&'a PartitionInfo> {
+    let mut partitions: Vec<&PartitionInfo> = list
+        .iter()
+        .filter(|p| p.environment == deploy.environment && p.country == deploy.country)
+        .copied()
+        .collect();
+    partitions.sort_by_key(|p| p.ordinal_position);
+    partitions
+}
+
+/// Method and expression on the first line, then the partitions with their bounds. Range and list
+/// partitions are left out with `ignore_boundaries`, as they roll forward over time, while the
+/// number of hash and key partitions is kept.
+pub(crate) fn partition_format(partitions: &[&PartitionInfo], ignore_boundaries: bool) -> String {
+    let first = partitions[0];
+    let mut scheme = format!(
+        "{}({})",
+        first.method,
+        first.expression.as_deref().unwrap_or_default()
+    );
+    if let Some(method) = &first.subpartition_method {
+        scheme += format!(
+            " SUBPARTITION BY {}({}) SUBPARTITIONS {}",
+            method,
+            first.subpartition_expression.as_deref().unwrap_or_default(),
+            first.subpartitions
+        )
+        .as_str();
+    }
+    let scheme = scheme.replace('`', "");
+    let bounded = first.method.contains("RANGE") || first.method.contains("LIST");
+    if ignore_boundaries && bounded {
+        return scheme;
+    }
+
+    let values = partitions
+        .iter()
+        .map(|p| match &p.bound {
+            Some(bound) => format!("{} {}", p.partition_name, bound),
+            None => p.partition_name.clone(),
+        })
+        .collect::<Vec<String>>()
+        .join(", ");
+    format!("{}\n{}", scheme, values)
+}
+
+/// Partition scheme of a table in the given deploy, [`NOT_PARTITIONED`] for plain tables and
+/// empty for tables absent from the deploy
+pub(crate) fn partition_value(
+    list: &[&PartitionInfo],
+    table: &UniqueTable,
+    tables: &[TableInfo],
+    deploy: &Deploy,
+    ignore_boundaries: bool,
+) -> String {
+    let partitions = find_partitions(list, deploy);
+    if !partitions.is_empty() {
+        return partition_format(&partitions, ignore_boundaries);
+    }
+    let exists = tables.iter().any(|t| {
+        t.schema == table.schema
+            && t.table == table.table
+            && t.environment == deploy.environment
+            && t.country == deploy.country
+    });
+    if exists {
+        NOT_PARTITIONED.to_string()
+    } else {
+        String::new()
+    }
+}
+
+pub(crate) fn find_most_used_partition(
+    deploys: &[Deploy],
+    list: &[&PartitionInfo],
+    table: &UniqueTable,
+    tables: &[TableInfo],
+    ignore_boundaries: bool,
+) -> String {
+    let values = deploys
+        .iter()
+        .map(|deploy| {
+            let value = partition_value(list, table, tables, deploy, ignore_boundaries);
+            if value.is_empty() {
+                Option::None
+            } else {
+                Some(value)
+            }
+        })
+        .collect();
+
+    select_reference(deploys, values).unwrap_or_default()
+}
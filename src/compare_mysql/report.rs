@@ -0,0 +1,164 @@
+use log::info;
+use std::{fs::File, io::BufReader, path::Path};
+
+use super::{
+    collector::{create_output, write_csv, write_missing_tables},
+    html::output_html,
+    json::{output_json, output_json_lines},
+    junit::output_junit,
+    markdown::output_markdown,
+    xlsx::output_xlsx,
+};
+use crate::value_object::{
+    Deploy, DiffRow, ObjectKind, OutputOptions, Result, SavedDeploy, SavedDiff,
+};
+
+/// Version of the saved diff format, increase it when the format changes
+pub const DIFF_VERSION: u32 = 1;
+
+const MISSING_TABLES_FILE: &str = "missing-tables.csv";
+
+/// Save a comparison of all objects, reports can be rendered from it by [`report`]
+pub fn save_diff(file: &str, deploys: &[Deploy], rows: &[DiffRow]) -> Result<()> {
+    info!("Saving diff to {}", file);
+    let diff = SavedDiff {
+        version: DIFF_VERSION,
+        created_at: chrono::Local::now().to_rfc3339(),
+        deploys: deploys
+            .iter()
+            .map(|d| SavedDeploy {
+                environment: d.environment.to_string(),
+                country: d.country.to_string(),
+                engine: d.engine,
+                unreachable: d.unreachable,
+                baseline: d.baseline,
+            })
+            .collect(),
+        rows: rows.to_vec(),
+    };
+
+    let writer = create_output(file)?;
+    serde_json::to_writer(writer, &diff)?;
+    Ok(())
+}
+
+/// Load a comparison saved by [`save_diff`]
+pub fn load_diff(file: &str) -> Result<SavedDiff> {
+    info!("Loading diff from {}", file);
+    let diff: SavedDiff = serde_json::from_reader(BufReader::new(File::open(file)?))?;
+    if diff.version == 0 || diff.version > DIFF_VERSION {
+        return Err(format!(
+            "Unsupported diff version {} in {}, expected up to {}",
+            diff.version, file, DIFF_VERSION
+        )
+        .into());
+    }
+    Ok(diff)
+}
+
+/// Render the reports of `options` from a saved comparison
+pub fn report(diff: &SavedDiff, options: &OutputOptions) -> Result<()> {
+    let deploys: Vec<Deploy> = diff
+        .deploys
+        .iter()
+        .map(|d| Deploy {
+            country: &d.country,
+            environment: &d.environment,
+            engine: d.engine,
+            unreachable: d.unreachable,
+            baseline: d.baseline,
+        })
+        .collect();
+    if let Some(dir) = &options.csv {
+        output_csv(
+            dir,
+            &deploys,
+            &diff.rows,
+            options.delimiter,
+            options.only_diff,
+        )?;
+    }
+    write_reports(&deploys, &diff.rows, options)
+}
+
+/// Write one csv file per object type into `dir`, and the tables missing in some deploys, named
+/// like the csv outputs of a comparison
+fn output_csv(
+    dir: &str,
+    deploys: &[Deploy],
+    rows: &[DiffRow],
+    delimiter: u8,
+    only_diff: bool,
+) -> Result<()> {
+    info!("Generating csv files to {}", dir);
+    for kind in ObjectKind::ALL {
+        if !rows.iter().any(|r| r.kind == kind) {
+            continue;
+        }
+        let file = Path::new(dir).join(csv_name(kind));
+        write_csv(
+            &file.to_string_lossy(),
+            delimiter,
+            only_diff,
+            kind,
+            deploys,
+            rows.iter().filter(|r| r.kind == kind).cloned(),
+        )?;
+    }
+    if rows.iter().any(|r| r.kind == ObjectKind::Table) {
+        let file = Path::new(dir).join(MISSING_TABLES_FILE);
+        write_missing_tables(&file.to_string_lossy(), deploys, rows, delimiter)?;
+    }
+    Ok(())
+}
+
+fn csv_name(kind: ObjectKind) -> &'static str {
+    match kind {
+        ObjectKind::Table => "diff-tables.csv",
+        ObjectKind::Column => "diff-columns.csv",
+        ObjectKind::Index => "diff-indices.csv",
+        ObjectKind::ForeignKey => "diff-foreign-keys.csv",
+        ObjectKind::Partition => "diff-partitions.csv",
+        ObjectKind::Trigger => "diff-triggers.csv",
+        ObjectKind::View => "diff-views.csv",
+        ObjectKind::Routine => "diff-routines.csv",
+        ObjectKind::Event => "diff-events.csv",
+    }
+}
+
+/// Any of the html, json, xlsx, markdown and junit reports is requested in `options`
+pub(crate) fn has_reports(options: &OutputOptions) -> bool {
+    options.html.is_some()
+        || options.json.is_some()
+        || options.json_lines.is_some()
+        || options.xlsx.is_some()
+        || options.markdown.is_some()
+        || options.junit.is_some()
+}
+
+/// Write the html, json, xlsx, markdown and junit reports requested in `options`
+pub(crate) fn write_reports(
+    deploys: &[Deploy],
+    rows: &[DiffRow],
+    options: &OutputOptions,
+) -> Result<()> {
+    if let Some(file) = &options.html {
+        output_html(file, deploys, rows, options.only_diff)?;
+    }
+    if let Some(file) = &options.json {
+        output_json(file, deploys, rows)?;
+    }
+    if let Some(file) = &options.json_lines {
+        output_json_lines(file, deploys, rows)?;
+    }
+    if let Some(file) = &options.xlsx {
+        output_xlsx(file, deploys, rows, options.only_diff)?;
+    }
+    if let Some(file) = &options.markdown {
+        output_markdown(file, deploys, rows, options.markdown_max_size)?;
+    }
+    if let Some(file) = &options.junit {
+        output_junit(file, deploys, rows)?;
+    }
+    Ok(())
+}
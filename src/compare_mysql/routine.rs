@@ -0,0 +1,382 @@
+use log::{debug, info};
+use similar::TextDiff;
+use std::{collections::BTreeMap, io::Write, mem};
+
+use super::{
+    collector::{create_output, select_reference, write_csv},
+    diff::routine_rows,
+};
+use crate::value_object::{
+    Deploy, DiffRow, Engine, ObjectKind, Result, RoutineInfo, UniqueRoutine,
+};
+
+/// Lines of context around changes in routine diffs
+const DIFF_CONTEXT: usize = 3;
+
+pub fn output_routine_info(
+    file: &str,
+    routines: &[RoutineInfo],
+    deploys: &[Deploy],
+    delimiter: u8,
+    only_diff: bool,
+) -> Result<()> {
+    debug!("Generating csv for {} routines", routines.len());
+    write_csv(
+        file,
+        delimiter,
+        only_diff,
+        ObjectKind::Routine,
+        deploys,
+        routine_rows(routines, deploys),
+    )
+}
+
+/// Write unified diffs of normalised routine definitions which differ from the reference
+/// definition, one per differing deploy, with one statement per line
+pub fn output_routine_diff(file: &str, routines: &[RoutineInfo], deploys: &[Deploy]) -> Result<()> {
+    info!("Generating routine diff to {}", file);
+    let mut writer = create_output(file)?;
+
+    let groups = group_routines(routines);
+    for row in routine_rows(routines, deploys).filter(|r| r.differs()) {
+        let key = UniqueRoutine {
+            schema: &row.schema,
+            routine_name: &row.name,
+            routine_type: &row.routine_type,
+        };
+        let list = &groups[&key];
+        let name = format!("{} {}.{}", row.routine_type, row.schema, row.name);
+
+        // definition of the first deploy holding the reference definition
+        let (reference, reference_deploy) = reference_definition(&row, list, deploys);
+        for (deploy, cell) in deploys.iter().zip(&row.cells).filter(|(_, c)| c.differs) {
+            let definition = find_routine(list, deploy)
+                .map(|r| diff_text(r, deploy.engine))
+                .unwrap_or_default();
+            let diff = TextDiff::from_lines(&reference, &definition);
+            write!(
+                writer,
+                "{}",
+                diff.unified_diff().context_radius(DIFF_CONTEXT).header(
+                    &format!("{} {} (reference)", name, reference_deploy),
+                    &format!(
+                        "{} [{} {}]{}",
+                        name,
+                        deploy.environment,
+                        deploy.country,
+                        if cell.value.is_empty() {
+                            " MISSING"
+                        } else {
+                            ""
+                        }
+                    ),
+                )
+            )?;
+            writeln!(writer)?;
+        }
+    }
+    writer.flush()?;
+    Ok(())
+}
+
+fn reference_definition(
+    row: &DiffRow,
+    list: &[&RoutineInfo],
+    deploys: &[Deploy],
+) -> (String, String) {
+    deploys
+        .iter()
+        .zip(&row.cells)
+        .filter(|(_, cell)| !cell.unreachable && !cell.differs)
+        .find_map(|(deploy, _)| {
+            find_routine(list, deploy).map(|r| {
+                (
+                    diff_text(r, deploy.engine),
+                    format!("[{} {}]", deploy.environment, deploy.country),
+                )
+            })
+        })
+        .unwrap_or((String::new(), "MISSING".to_string()))
+}
+
+/// Signature and normalised statements of a routine, each on a line of its own
+fn diff_text(item: &RoutineInfo, engine: Engine) -> String {
+    let mut lines = vec![signature(item)];
+    lines.extend(normalize_statements(&item.definition, engine));
+    lines.join("\n") + "\n"
+}
+
+pub(crate) fn group_routines(
+    routines: &[RoutineInfo],
+) -> BTreeMap<UniqueRoutine<'_>, Vec<&RoutineInfo>> {
+    routines.iter().fold(BTreeMap::new(), |mut acc, r| {
+        acc.entry(UniqueRoutine {
+            schema: &r.schema,
+            routine_name: &r.routine_name,
+            routine_type: &r.routine_type,
+        })
+        .or_default()
+        .push(r);
+        acc
+    })
+}
+
+pub(crate) fn find_routine<'a>(
+    list: &[&'a RoutineInfo],
+    deploy: &Deploy,
+) -> Option<&'a RoutineInfo> {
+    list.iter()
+        .find(|r| r.environment == deploy.environment && r.country == deploy.country)
+        .copied()
+}
+
+fn signature(item: &RoutineInfo) -> String {
+    let mut signature = format!("{}({})", item.routine_type, item.parameters);
+    if let Some(returns) = &item.returns {
+        signature += format!(" RETURNS {}", returns).as_str();
+    }
+    signature
+}
+
+/// Signature on the first line, then the normalised definition
+pub(crate) fn routine_format(item: &RoutineInfo, engine: Engine) -> String {
+    format!(
+        "{}\n{}",
+        signature(item),
+        normalize_definition(&item.definition, engine)
+    )
+}
+
+pub(crate) fn find_most_used_routine(deploys: &[Deploy], list: &[&RoutineInfo]) -> String {
+    let values = deploys
+        .iter()
+        .map(|deploy| find_routine(list, deploy).map(|r| routine_format(r, deploy.engine)))
+        .collect();
+
+    select_reference(deploys, values).unwrap_or_default()
+}
+
+/// Drop comments and the `DEFINER` clause of the create header, and collapse whitespace of a create
+/// statement, quoted text is kept as is apart from whitespace. Comments and quotes follow the syntax of `engine`.
+pub fn normalize_definition(definition: &str, engine: Engine) -> String {
+    normalize_statements(definition, engine).join(" ")
+}
+
+/// Statements of a definition normalised like [`normalize_definition`]
+pub(crate) fn normalize_statements(definition: &str, engine: Engine) -> Vec<String> {
+    let chars: Vec<char> = definition.chars().collect();
+    split_statements(&chars, engine, false)
+        .iter()
+        .map(|statement| {
+            let statement = statement
+                .split_whitespace()
+                .collect::<Vec<&str>>()
+                .join(" ");
+            strip_definer(&statement, engine)
+        })
+        .filter(|statement| !statement.is_empty())
+        .collect()
+}
+
+/// Objects a `DEFINER` clause of a create header belongs to
+const DEFINED_OBJECTS: [&str; 5] = ["PROCEDURE", "FUNCTION", "TRIGGER", "EVENT", "VIEW"];
+
+/// Drop `DEFINER=` between `CREATE` and the object keyword, e.g. in
+/// ``CREATE DEFINER=`root`@`%` PROCEDURE``. Statements which aren't such a header are kept as is.
+fn strip_definer(statement: &str, engine: Engine) -> String {
+    let chars: Vec<char> = statement.chars().collect();
+    // tokens separated by single spaces, quoted text stays within its token
+    let mut tokens = vec![];
+    let mut start = 0;
+    let mut i = 0;
+    while i <= chars.len() {
+        match chars.get(i) {
+            Some('\'' | '"' | '`') => {
+                i = quoted_end(&chars, i, engine);
+                continue;
+            }
+            Some(' ') | Option::None => {
+                tokens.push(chars[start..i].iter().collect::<String>());
+                start = i + 1;
+            }
+            _ => {}
+        }
+        i += 1;
+    }
+
+    if !tokens[0].eq_ignore_ascii_case("CREATE") {
+        return statement.to_string();
+    }
+    let mut header = vec![tokens[0].as_str()];
+    for (index, token) in tokens.iter().enumerate().skip(1) {
+        let upper = token.to_uppercase();
+        if DEFINED_OBJECTS.contains(&upper.as_str()) {
+            header.extend(tokens[index..].iter().map(String::as_str));
+            return header.join(" ");
+        }
+        if token.contains('(') {
+            break;
+        }
+        if !upper.starts_with("DEFINER=") {
+            header.push(token);
+        }
+    }
+    statement.to_string()
+}
+
+/// Text of `chars` without comments, split after each `;` outside quotes.
+///
+/// The outermost dollar quoted text of PostgreSQL is the body of a routine and split as well,
+/// dollar quotes nested in a body are kept as is.
+fn split_statements(chars: &[char], engine: Engine, in_body: bool) -> Vec<String> {
+    let mut statements = vec![];
+    let mut current = String::new();
+    let mut i = 0;
+    while i < chars.len() {
+        let c = chars[i];
+        match c {
+            '\'' | '"' | '`' => {
+                let end = quoted_end(chars, i, engine);
+                current.extend(&chars[i..end]);
+                i = end;
+                continue;
+            }
+            '$' if engine == Engine::Postgres => {
+                if let Some(tag_end) = dollar_tag_end(chars, i) {
+                    let tag = &chars[i..tag_end];
+                    let close = chars[tag_end..]
+                        .windows(tag.len())
+                        .position(|w| w == tag)
+                        .map(|p| p + tag_end)
+                        .unwrap_or(chars.len());
+                    let end = (close + tag.len()).min(chars.len());
+                    if in_body {
+                        current.extend(&chars[i..end]);
+                    } else {
+                        current.extend(tag);
+                        let mut body =
+                            split_statements(&chars[tag_end..close], engine, true).into_iter();
+                        current += body.next().unwrap_or_default().as_str();
+                        for statement in body {
+                            statements.push(mem::take(&mut current));
+                            current = statement;
+                        }
+                        current.extend(&chars[close..end]);
+                    }
+                    i = end;
+                    continue;
+                }
+                current.push(c);
+            }
+            '-' if line_comment(chars, i, engine) => {
+                while i < chars.len() && chars[i] != '\n' {
+                    i += 1;
+                }
+                current.push(' ');
+                continue;
+            }
+            '#' if engine == Engine::Mysql => {
+                while i < chars.len() && chars[i] != '\n' {
+                    i += 1;
+                }
+                current.push(' ');
+                continue;
+            }
+            '/' if chars.get(i + 1) == Some(&'*') => {
+                i += 2;
+                while i < chars.len() && !(chars[i - 1] == '*' && chars[i] == '/') {
+                    i += 1;
+                }
+                current.push(' ');
+            }
+            ';' => {
+                current.push(c);
+                statements.push(mem::take(&mut current));
+            }
+            _ => current.push(c),
+        }
+        i += 1;
+    }
+    statements.push(current);
+    statements
+}
+
+/// Text of a normalised definition split into code and quoted text, `true` for quoted text.
+/// MySQL identifiers quoted with backticks are code and their backticks are dropped.
+pub(crate) fn split_quoted(text: &str, engine: Engine) -> Vec<(String, bool)> {
+    let chars: Vec<char> = text.chars().collect();
+    let mut parts: Vec<(String, bool)> = vec![];
+    let mut push = |part: String, quoted: bool| match parts.last_mut() {
+        Some((last, false)) if !quoted => last.push_str(&part),
+        _ => parts.push((part, quoted)),
+    };
+    let mut start = 0;
+    let mut i = 0;
+    while i < chars.len() {
+        match chars[i] {
+            '\'' | '"' | '`' => {
+                let end = quoted_end(&chars, i, engine);
+                push(chars[start..i].iter().collect(), false);
+                if chars[i] == '`' {
+                    push(chars[i..end].iter().filter(|c| **c != '`').collect(), false);
+                } else {
+                    push(chars[i..end].iter().collect(), true);
+                }
+                start = end;
+                i = end;
+            }
+            _ => i += 1,
+        }
+    }
+    push(chars[start..].iter().collect(), false);
+    parts
+}
+
+/// Index after the quote closing the one at `start`, MySQL escapes quotes with a backslash as well
+/// as by doubling them
+fn quoted_end(chars: &[char], start: usize, engine: Engine) -> usize {
+    let quote = chars[start];
+    let mut i = start + 1;
+    while i < chars.len() {
+        if chars[i] == '\\' && engine == Engine::Mysql && quote != '`' {
+            i += 2;
+            continue;
+        }
+        if chars[i] == quote {
+            return i + 1;
+        }
+        i += 1;
+    }
+    chars.len()
+}
+
+/// Index after a dollar quote tag like `$$` or `$body$` at `start`, not after parameters like `$1`
+fn dollar_tag_end(chars: &[char], start: usize) -> Option<usize> {
+    let identifier = |c: &char| c.is_alphanumeric() || *c == '_';
+    if start > 0 && identifier(&chars[start - 1]) {
+        return Option::None;
+    }
+    let mut i = start + 1;
+    while i < chars.len() && identifier(&chars[i]) {
+        i += 1;
+    }
+    let numbered = chars
+        .get(start + 1)
+        .map(|c| c.is_ascii_digit())
+        .unwrap_or(false);
+    if i < chars.len() && chars[i] == '$' && !numbered {
+        Some(i + 1)
+    } else {
+        Option::None
+    }
+}
+
+/// `--` starts a comment, in MySQL only when followed by whitespace
+fn line_comment(chars: &[char], start: usize, engine: Engine) -> bool {
+    chars.get(start + 1) == Some(&'-')
+        && (engine != Engine::Mysql
+            || chars
+                .get(start + 2)
+                .map(|c| c.is_whitespace() || c.is_control())
+                .unwrap_or(true))
+}
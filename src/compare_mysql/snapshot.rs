@@ -0,0 +1,125 @@
+use log::info;
+use serde::{de::DeserializeOwned, Serialize};
+use std::{
+    fs::{self, OpenOptions},
+    io::BufWriter,
+    path::Path,
+};
+
+use crate::value_object::{Result, Snapshot, SnapshotManifest};
+
+/// Version of the snapshot layout, increase it when the format of saved files changes
+///
+/// - 1: columns, indices and foreign keys
+/// - 2: tables
+/// - 3: routines
+/// - 4: triggers
+/// - 5: views
+/// - 6: events
+/// - 7: partitions
+pub const SNAPSHOT_VERSION: u32 = 7;
+
+const MANIFEST_FILE: &str = "manifest.json";
+const TABLES_FILE: &str = "tables.json";
+const COLUMNS_FILE: &str = "columns.json";
+const INDICES_FILE: &str = "indices.json";
+const FOREIGN_KEYS_FILE: &str = "foreign-keys.json";
+const PARTITIONS_FILE: &str = "partitions.json";
+const TRIGGERS_FILE: &str = "triggers.json";
+const VIEWS_FILE: &str = "views.json";
+const ROUTINES_FILE: &str = "routines.json";
+const EVENTS_FILE: &str = "events.json";
+
+/// Save collected schema info into `dir`, along with a manifest of the sources (without password)
+pub fn save_snapshot(dir: &str, snapshot: &Snapshot) -> Result<()> {
+    info!("Saving snapshot to {}", dir);
+    fs::create_dir_all(dir)?;
+
+    let manifest = SnapshotManifest {
+        version: SNAPSHOT_VERSION,
+        created_at: chrono::Local::now().to_rfc3339(),
+        sources: snapshot.sources.clone(),
+        failures: snapshot.failures.clone(),
+    };
+
+    write_json(dir, MANIFEST_FILE, &manifest)?;
+    write_json(dir, TABLES_FILE, &snapshot.tables)?;
+    write_json(dir, COLUMNS_FILE, &snapshot.columns)?;
+    write_json(dir, INDICES_FILE, &snapshot.indices)?;
+    write_json(dir, FOREIGN_KEYS_FILE, &snapshot.foreign_keys)?;
+    write_json(dir, PARTITIONS_FILE, &snapshot.partitions)?;
+    write_json(dir, TRIGGERS_FILE, &snapshot.triggers)?;
+    write_json(dir, VIEWS_FILE, &snapshot.views)?;
+    write_json(dir, ROUTINES_FILE, &snapshot.routines)?;
+    write_json(dir, EVENTS_FILE, &snapshot.events)?;
+    Ok(())
+}
+
+/// Load schema info saved by [`save_snapshot`]
+pub fn load_snapshot(dir: &str) -> Result<Snapshot> {
+    info!("Loading snapshot from {}", dir);
+
+    let manifest: SnapshotManifest = read_json(dir, MANIFEST_FILE)?;
+    if manifest.version == 0 || manifest.version > SNAPSHOT_VERSION {
+        return Err(format!(
+            "Unsupported snapshot version {} in {}, expected up to {}",
+            manifest.version, dir, SNAPSHOT_VERSION
+        )
+        .into());
+    }
+
+    Ok(Snapshot {
+        sources: manifest.sources,
+        tables: if manifest.version >= 2 {
+            read_json(dir, TABLES_FILE)?
+        } else {
+            vec![]
+        },
+        columns: read_json(dir, COLUMNS_FILE)?,
+        indices: read_json(dir, INDICES_FILE)?,
+        foreign_keys: read_json(dir, FOREIGN_KEYS_FILE)?,
+        partitions: if manifest.version >= 7 {
+            read_json(dir, PARTITIONS_FILE)?
+        } else {
+            vec![]
+        },
+        triggers: if manifest.version >= 4 {
+            read_json(dir, TRIGGERS_FILE)?
+        } else {
+            vec![]
+        },
+        views: if manifest.version >= 5 {
+            read_json(dir, VIEWS_FILE)?
+        } else {
+            vec![]
+        },
+        routines: if manifest.version >= 3 {
+            read_json(dir, ROUTINES_FILE)?
+        } else {
+            vec![]
+        },
+        events: if manifest.version >= 6 {
+            read_json(dir, EVENTS_FILE)?
+        } else {
+            vec![]
+        },
+        failures: manifest.failures,
+    })
+}
+
+fn write_json<T: Serialize + ?Sized>(dir: &str, name: &str, value: &T) -> Result<()> {
+    let file = OpenOptions::new()
+        .write(true)
+        .truncate(true)
+        .create(true)
+        .open(Path::new(dir).join(name))?;
+
+    let writer = BufWriter::new(file);
+    serde_json::to_writer(writer, value)?;
+    Ok(())
+}
+
+fn read_json<T: DeserializeOwned>(dir: &str, name: &str) -> Result<T> {
+    let json = fs::read_to_string(Path::new(dir).join(name))?;
+    Ok(serde_json::from_str(&json)?)
+}
@@ -0,0 +1,74 @@
+use log::debug;
+use std::collections::BTreeMap;
+
+use super::{
+    collector::{select_reference, write_csv},
+    diff::trigger_rows,
+    routine::normalize_definition,
+};
+use crate::value_object::{Deploy, Engine, ObjectKind, Result, TriggerInfo, UniqueTrigger};
+
+pub fn output_trigger_info(
+    file: &str,
+    triggers: &[TriggerInfo],
+    deploys: &[Deploy],
+    delimiter: u8,
+    only_diff: bool,
+) -> Result<()> {
+    debug!("Generating csv for {} triggers", triggers.len());
+    write_csv(
+        file,
+        delimiter,
+        only_diff,
+        ObjectKind::Trigger,
+        deploys,
+        trigger_rows(triggers, deploys),
+    )
+}
+
+pub(crate) fn group_triggers(
+    triggers: &[TriggerInfo],
+) -> BTreeMap<UniqueTrigger<'_>, Vec<&TriggerInfo>> {
+    triggers.iter().fold(
+        BTreeMap::new(),
+        |mut acc: BTreeMap<UniqueTrigger, Vec<&TriggerInfo>>, t| {
+            acc.entry(UniqueTrigger {
+                schema: &t.schema,
+                table: &t.table,
+                trigger_name: &t.trigger_name,
+            })
+            .or_default()
+            .push(t);
+            acc
+        },
+    )
+}
+
+pub(crate) fn find_trigger<'a>(
+    list: &[&'a TriggerInfo],
+    deploy: &Deploy,
+) -> Option<&'a TriggerInfo> {
+    list.iter()
+        .find(|t| t.environment == deploy.environment && t.country == deploy.country)
+        .copied()
+}
+
+/// Timing, event and the normalised statement, the definer differs between environments and is
+/// left out
+pub(crate) fn trigger_format(item: &TriggerInfo, engine: Engine) -> String {
+    format!(
+        "{} {} {}",
+        item.timing,
+        item.event,
+        normalize_definition(&item.statement, engine)
+    )
+}
+
+pub(crate) fn find_most_used_trigger(deploys: &[Deploy], list: &[&TriggerInfo]) -> String {
+    let values = deploys
+        .iter()
+        .map(|deploy| find_trigger(list, deploy).map(|t| trigger_format(t, deploy.engine)))
+        .collect();
+
+    select_reference(deploys, values).unwrap_or_default()
+}
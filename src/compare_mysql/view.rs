@@ -0,0 +1,115 @@
+use log::debug;
+use std::collections::BTreeMap;
+
+use super::{
+    collector::{select_reference, write_csv},
+    diff::view_rows,
+    routine::{normalize_definition, split_quoted},
+};
+use crate::value_object::{Deploy, Engine, ObjectKind, Result, UniqueView, ViewInfo};
+
+pub fn output_view_info(
+    file: &str,
+    views: &[ViewInfo],
+    deploys: &[Deploy],
+    delimiter: u8,
+    only_diff: bool,
+) -> Result<()> {
+    debug!("Generating csv for {} views", views.len());
+    write_csv(
+        file,
+        delimiter,
+        only_diff,
+        ObjectKind::View,
+        deploys,
+        view_rows(views, deploys),
+    )
+}
+
+pub(crate) fn group_views(views: &[ViewInfo]) -> BTreeMap<UniqueView<'_>, Vec<&ViewInfo>> {
+    views.iter().fold(BTreeMap::new(), |mut acc, v| {
+        acc.entry(UniqueView {
+            schema: &v.schema,
+            view_name: &v.view_name,
+        })
+        .or_default()
+        .push(v);
+        acc
+    })
+}
+
+pub(crate) fn find_view<'a>(list: &[&'a ViewInfo], deploy: &Deploy) -> Option<&'a ViewInfo> {
+    list.iter()
+        .find(|v| v.environment == deploy.environment && v.country == deploy.country)
+        .copied()
+}
+
+/// Columns and options on the first line, then the normalised definition
+pub(crate) fn view_format(item: &ViewInfo, engine: Engine) -> String {
+    let mut options = vec![format!("({})", item.columns)];
+    if item.check_option != "NONE" {
+        options.push(format!("WITH {} CHECK OPTION", item.check_option));
+    }
+    if let Some(security_type) = &item.security_type {
+        options.push(format!("SQL SECURITY {}", security_type));
+    }
+    let updatable = if item.updatable {
+        "UPDATABLE"
+    } else {
+        "READ ONLY"
+    };
+    options.push(updatable.to_string());
+
+    format!(
+        "{}\n{}",
+        options.join(" "),
+        normalize_view_definition(&item.definition, &item.schema, engine)
+    )
+}
+
+pub(crate) fn find_most_used_view(deploys: &[Deploy], list: &[&ViewInfo]) -> String {
+    let values = deploys
+        .iter()
+        .map(|deploy| find_view(list, deploy).map(|v| view_format(v, deploy.engine)))
+        .collect();
+
+    select_reference(deploys, values).unwrap_or_default()
+}
+
+/// Normalise a view definition like [`normalize_definition`], and drop the backticks and the
+/// qualifiers of the view's own schema MySQL adds to every name when it stores the definition.
+/// String literals are kept as is.
+pub fn normalize_view_definition(definition: &str, schema: &str, engine: Engine) -> String {
+    let text = normalize_definition(definition, engine);
+    split_quoted(&text, engine)
+        .into_iter()
+        .map(|(part, quoted)| {
+            if quoted {
+                part
+            } else {
+                strip_qualifier(&part, schema)
+            }
+        })
+        .collect()
+}
+
+/// Drop `schema.` in front of names, names which only end with the schema name are kept
+fn strip_qualifier(text: &str, schema: &str) -> String {
+    let qualifier = format!("{}.", schema);
+    let mut result = String::with_capacity(text.len());
+    let mut rest = text;
+    while let Some(index) = rest.find(&qualifier) {
+        let partial = rest[..index]
+            .chars()
+            .next_back()
+            .map(|c| c.is_alphanumeric() || c == '_')
+            .unwrap_or(false);
+        result.push_str(&rest[..index]);
+        if partial {
+            result.push_str(&qualifier);
+        }
+        rest = &rest[index + qualifier.len()..];
+    }
+    result.push_str(rest);
+    result
+}
@@ -0,0 +1,188 @@
+use log::info;
+use rust_xlsxwriter::{Color, Format, Workbook, Worksheet};
+use std::{fs, path::Path};
+
+use super::diff::{display_value, is_missing};
+use crate::value_object::{Deploy, DiffRow, ObjectKind, Result};
+
+/// Most characters Excel keeps in a cell
+const MAX_CELL_CHARS: usize = 32_767;
+
+/// Characters kept of longer values, before [`TRUNCATED`]
+const TRUNCATED_CHARS: usize = 32_000;
+
+const TRUNCATED: &str = "…(truncated)";
+
+/// Write an Excel workbook with a summary sheet and one sheet per object kind.
+///
+/// Header rows are frozen with an autofilter, and cells which differ from the reference
+/// definition are highlighted. With `only_diff`, identical objects are counted below each sheet
+/// instead.
+pub fn output_xlsx(
+    file: &str,
+    deploys: &[Deploy],
+    rows: &[DiffRow],
+    only_diff: bool,
+) -> Result<()> {
+    info!("Generating xlsx workbook to {}", file);
+    if let Some(parent) = Path::new(file).parent().filter(|p| !p.exists()) {
+        fs::create_dir_all(parent)?;
+    }
+
+    let mut workbook = Workbook::new();
+    write_summary(workbook.add_worksheet(), deploys, rows)?;
+    for kind in ObjectKind::ALL {
+        let (list, identical): (Vec<&DiffRow>, Vec<&DiffRow>) = rows
+            .iter()
+            .filter(|r| r.kind == kind)
+            .partition(|r| !only_diff || r.differs());
+        if list.is_empty() && identical.is_empty() {
+            continue;
+        }
+        let sheet = workbook.add_worksheet();
+        write_sheet(sheet, kind, deploys, &list)?;
+        if only_diff {
+            sheet.write_string(
+                list.len() as u32 + 2,
+                0,
+                format!("{} identical objects omitted", identical.len()),
+            )?;
+        }
+    }
+    workbook.save(file)?;
+    Ok(())
+}
+
+fn header_format() -> Format {
+    Format::new()
+        .set_bold()
+        .set_background_color(Color::RGB(0xF3F3F3))
+}
+
+fn write_summary(sheet: &mut Worksheet, deploys: &[Deploy], rows: &[DiffRow]) -> Result<()> {
+    sheet.set_name("Summary")?;
+    let header = header_format();
+
+    let mut titles = vec!["Environment", "Country", "Baseline", "Unreachable"];
+    titles.extend(ObjectKind::ALL.iter().map(|k| k.label()));
+    titles.push("Total");
+    for (col, title) in titles.iter().enumerate() {
+        sheet.write_string_with_format(0, col as u16, *title, &header)?;
+    }
+
+    // count of differing objects of each kind per deploy
+    for (index, deploy) in deploys.iter().enumerate() {
+        let row = index as u32 + 1;
+        sheet.write_string(row, 0, deploy.environment)?;
+        sheet.write_string(row, 1, deploy.country)?;
+        sheet.write_boolean(row, 2, deploy.baseline)?;
+        sheet.write_boolean(row, 3, deploy.unreachable)?;
+
+        let mut total = 0;
+        for (offset, kind) in ObjectKind::ALL.iter().enumerate() {
+            let count = rows
+                .iter()
+                .filter(|r| r.kind == *kind && r.cells[index].differs)
+                .count();
+            total += count;
+            sheet.write_number(row, 4 + offset as u16, count as u32)?;
+        }
+        sheet.write_number(row, 4 + ObjectKind::ALL.len() as u16, total as u32)?;
+    }
+
+    sheet.set_freeze_panes(1, 0)?;
+    sheet.autofit();
+    Ok(())
+}
+
+fn write_sheet(
+    sheet: &mut Worksheet,
+    kind: ObjectKind,
+    deploys: &[Deploy],
+    rows: &[&DiffRow],
+) -> Result<()> {
+    sheet.set_name(sheet_name(kind))?;
+    let header = header_format();
+    let outlier = Format::new().set_background_color(Color::RGB(0xFBD5D5));
+    let missing = Format::new().set_background_color(Color::RGB(0xFDE9C8));
+    let unreachable = Format::new()
+        .set_background_color(Color::RGB(0xE2E2E2))
+        .set_font_color(Color::RGB(0x777777));
+
+    let mut titles = vec!["Schema".to_string()];
+    if kind.in_table() {
+        titles.push("Table".to_string());
+    }
+    if kind.named() {
+        titles.push(kind.label().to_string());
+    }
+    if kind == ObjectKind::Routine {
+        titles.push("Type".to_string());
+    }
+    let keys = titles.len() as u16;
+    titles.extend(deploys.iter().map(|d| {
+        format!(
+            "[{} {}]{}",
+            d.environment,
+            d.country,
+            if d.baseline { " (baseline)" } else { "" }
+        )
+    }));
+    for (col, title) in titles.iter().enumerate() {
+        sheet.write_string_with_format(0, col as u16, title, &header)?;
+    }
+
+    for (index, row) in rows.iter().enumerate() {
+        let line = index as u32 + 1;
+        sheet.write_string(line, 0, &row.schema)?;
+        if kind.in_table() {
+            sheet.write_string(line, 1, &row.table)?;
+        }
+        if kind == ObjectKind::Routine {
+            sheet.write_string(line, keys - 2, &row.name)?;
+            sheet.write_string(line, keys - 1, &row.routine_type)?;
+        } else if kind.named() {
+            sheet.write_string(line, keys - 1, &row.name)?;
+        }
+        for (offset, cell) in row.cells.iter().enumerate() {
+            let col = keys + offset as u16;
+            let value = &cell_value(display_value(cell));
+            if cell.unreachable {
+                sheet.write_string_with_format(line, col, value, &unreachable)?;
+            } else if cell.differs && is_missing(cell) {
+                sheet.write_string_with_format(line, col, value, &missing)?;
+            } else if cell.differs {
+                sheet.write_string_with_format(line, col, value, &outlier)?;
+            } else {
+                sheet.write_string(line, col, value)?;
+            }
+        }
+    }
+
+    sheet.set_freeze_panes(1, 0)?;
+    sheet.autofilter(0, 0, rows.len() as u32, titles.len() as u16 - 1)?;
+    sheet.autofit();
+    Ok(())
+}
+
+/// Value cut to fit in a cell, long routine bodies exceed the limit of Excel
+fn cell_value(value: String) -> String {
+    if value.chars().count() <= MAX_CELL_CHARS {
+        return value;
+    }
+    value.chars().take(TRUNCATED_CHARS).collect::<String>() + TRUNCATED
+}
+
+fn sheet_name(kind: ObjectKind) -> &'static str {
+    match kind {
+        ObjectKind::Table => "Tables",
+        ObjectKind::Column => "Columns",
+        ObjectKind::Index => "Indices",
+        ObjectKind::ForeignKey => "Foreign Keys",
+        ObjectKind::Partition => "Partitions",
+        ObjectKind::Trigger => "Triggers",
+        ObjectKind::View => "Views",
+        ObjectKind::Routine => "Routines",
+        ObjectKind::Event => "Events",
+    }
+}
@@ -1,34 +1,241 @@
-use clap::{Arg, Command};
-use log::{debug, info};
-use std::time::Instant;
+use clap::{Arg, ArgMatches, Command};
+use log::{debug, error, info, warn};
+use std::{
+    process,
+    time::{Duration, Instant},
+};
 
-use scomp::value_object::Result;
+use scomp::value_object::{
+    CollectOptions, CompareRules, Drift, DriftCategory, OutputOptions, Result,
+};
 use scomp::{
-    compare_mysql::{read_config, start},
+    compare_mysql::{
+        collect, compare, load_diff, load_snapshot, read_config, read_rules, report, save_snapshot,
+        start,
+    },
     init_log,
 };
 
+/// Exit code of a check finding differences in failing categories
+const EXIT_DRIFT: i32 = 1;
+/// Exit code of a check which couldn't collect or compare all databases
+const EXIT_ERROR: i32 = 2;
+
+/// Formats rendered by the `report` command
+const REPORT_FORMATS: [&str; 7] = [
+    "html",
+    "json",
+    "json-lines",
+    "xlsx",
+    "markdown",
+    "junit",
+    "csv",
+];
+
 fn main() -> Result<()> {
-    let cmd = command_args();
-    let args = cmd.clone().get_matches();
-    let config_file = args.value_of("config").unwrap();
-    let columns_output_file = args.value_of("diff-columns").unwrap();
-    let indice_output_file = args.value_of("diff-indices").unwrap();
+    let args = command_args().get_matches();
     let level = args.value_of("level").unwrap();
-    let source = args.is_present("source");
-
     init_log(level)?;
 
-    debug!(
-        "Args: config = {}, columns output = {}, indices output = {}, level = {}",
-        config_file, columns_output_file, indice_output_file, level
-    );
-    let configs = read_config(config_file)?;
-
     let now = Instant::now();
-    start(&configs, source, columns_output_file, indice_output_file)?;
+    let result = match args.subcommand() {
+        Some(("collect", args)) => run_collect(args),
+        Some(("diff", args)) => run_diff(args),
+        Some(("report", args)) => run_report(args),
+        Some(("migrate", args)) => run_migrate(args),
+        Some(("check", args)) => {
+            let code = run_check(args);
+            info!("Time elapsed {}s", now.elapsed().as_secs());
+            process::exit(code);
+        }
+        _ => unreachable!("a subcommand is required"),
+    };
     info!("Time elapsed {}s", now.elapsed().as_secs());
 
+    result
+}
+
+/// Collect schema info of all databases into a snapshot directory
+fn run_collect(args: &ArgMatches) -> Result<()> {
+    let configs = read_config(args.value_of("config").unwrap())?;
+    let collect_options = collect_options(args)?;
+    debug!("Args: collect = {:?}", collect_options);
+
+    let snapshot = collect(&configs, &collect_options)?;
+    save_snapshot(args.value_of("out").unwrap(), &snapshot)
+}
+
+/// Compare databases or a snapshot, and write the requested outputs
+fn run_diff(args: &ArgMatches) -> Result<()> {
+    let mut options = compare_options(args)?;
+    // with --no-csv, default file names are ignored and only given csv outputs are written
+    let csv = |name: &str| {
+        if args.is_present("no-csv") && args.occurrences_of(name) == 0 {
+            None
+        } else {
+            args.value_of(name).map(String::from)
+        }
+    };
+    options.columns = csv("diff-columns");
+    options.indices = csv("diff-indices");
+    options.foreign_keys = csv("diff-foreign-keys");
+    options.partitions = csv("diff-partitions");
+    options.triggers = csv("diff-triggers");
+    options.views = csv("diff-views");
+    options.routines = csv("diff-routines");
+    options.routine_diff = args.value_of("routine-diff").map(String::from);
+    options.events = csv("diff-events");
+    options.tables = csv("diff-tables");
+    options.missing_tables = csv("missing-tables");
+    options.snapshot = args.value_of("snapshot-out").map(String::from);
+    options.diff = args.value_of("save").map(String::from);
+    options.html = args.value_of("html").map(String::from);
+    options.json = args.value_of("json").map(String::from);
+    options.json_lines = args.value_of("json-lines").map(String::from);
+    options.xlsx = args.value_of("xlsx").map(String::from);
+    options.markdown = args.value_of("markdown").map(String::from);
+    options.junit = args.value_of("junit").map(String::from);
+    report_options(args, &mut options)?;
+
+    compare_source(args, &options)?;
+    Ok(())
+}
+
+/// Render a diff saved by the `diff` command
+fn run_report(args: &ArgMatches) -> Result<()> {
+    let mut options = OutputOptions::default();
+    let output = args.value_of("output").map(String::from);
+    match args.value_of("format").unwrap() {
+        "html" => options.html = output,
+        "json" => options.json = output,
+        "json-lines" => options.json_lines = output,
+        "xlsx" => options.xlsx = output,
+        "markdown" => options.markdown = output,
+        "junit" => options.junit = output,
+        "csv" => options.csv = output,
+        format => return Err(format!("Unknown report format {}", format).into()),
+    }
+    report_options(args, &mut options)?;
+    debug!("Args: output = {:?}", options);
+
+    let diff = load_diff(args.value_of("diff").unwrap())?;
+    report(&diff, &options)
+}
+
+/// Write migration scripts towards the reference definition
+fn run_migrate(args: &ArgMatches) -> Result<()> {
+    let mut options = compare_options(args)?;
+    options.migration = args.value_of("out").map(String::from);
+
+    compare_source(args, &options)?;
+    Ok(())
+}
+
+/// Compare without writing outputs, and return the exit code of the check
+fn run_check(args: &ArgMatches) -> i32 {
+    let result = || -> Result<(Drift, Vec<DriftCategory>)> {
+        let fail_on = match args.values_of("fail-on") {
+            Some(values) => values
+                .map(|v| v.parse::<DriftCategory>())
+                .collect::<std::result::Result<Vec<_>, _>>()?,
+            None => DriftCategory::ALL.to_vec(),
+        };
+        let options = OutputOptions {
+            check: true,
+            ..compare_options(args)?
+        };
+        let drift = compare_source(args, &options)?.ok_or("No drift counted by the check")?;
+        Ok((drift, fail_on))
+    };
+
+    match result() {
+        Ok((drift, fail_on)) => check_drift(&drift, &fail_on),
+        Err(e) => {
+            error!("Check failed: {}", e);
+            EXIT_ERROR
+        }
+    }
+}
+
+/// Log differences found, as errors in `fail_on` categories and warnings otherwise, and return the
+/// exit code of the check
+fn check_drift(drift: &Drift, fail_on: &[DriftCategory]) -> i32 {
+    for (category, count) in &drift.counts {
+        if fail_on.contains(category) {
+            error!("{} objects differ: {}", count, category.name());
+        } else {
+            warn!("{} objects differ: {}", count, category.name());
+        }
+    }
+
+    if drift.unreachable > 0 {
+        error!("{} databases are unreachable", drift.unreachable);
+        EXIT_ERROR
+    } else if drift.count(fail_on) > 0 {
+        EXIT_DRIFT
+    } else {
+        info!("No schema drift found");
+        0
+    }
+}
+
+/// Compare a saved snapshot, or databases of the connection config
+fn compare_source(args: &ArgMatches, options: &OutputOptions) -> Result<Option<Drift>> {
+    match args.value_of("from-snapshot") {
+        Some(dir) => {
+            debug!("Args: snapshot = {}, output = {:?}", dir, options);
+            compare(&load_snapshot(dir)?, options)
+        }
+        None => {
+            let configs = read_config(args.value_of("config").unwrap())?;
+            let collect_options = collect_options(args)?;
+            debug!(
+                "Args: collect = {:?}, output = {:?}",
+                collect_options, options
+            );
+            start(&configs, &collect_options, options)
+        }
+    }
+}
+
+fn collect_options(args: &ArgMatches) -> Result<CollectOptions> {
+    Ok(CollectOptions {
+        jobs: args.value_of_t("jobs")?,
+        continue_on_error: args.is_present("continue-on-error"),
+        retries: args.value_of_t("retries")?,
+        retry_delay: Duration::from_millis(args.value_of_t("retry-delay")?),
+        timeout: match args.value_of("timeout") {
+            Some(_) => Some(Duration::from_secs(args.value_of_t("timeout")?)),
+            None => None,
+        },
+    })
+}
+
+/// Options of the comparison itself, without any output
+fn compare_options(args: &ArgMatches) -> Result<OutputOptions> {
+    Ok(OutputOptions {
+        ignore_default_collation: args.is_present("ignore-default-collation"),
+        ignore_partition_boundaries: args.is_present("ignore-partition-boundaries"),
+        baseline: args.value_of("baseline").map(String::from),
+        rules: match args.value_of("rules") {
+            Some(file) => read_rules(file)?,
+            None => CompareRules::default(),
+        },
+        ..Default::default()
+    })
+}
+
+fn report_options(args: &ArgMatches, options: &mut OutputOptions) -> Result<()> {
+    options.only_diff = args.is_present("only-diff");
+    options.markdown_max_size = match args.value_of("markdown-max-size") {
+        Some(_) => Some(args.value_of_t("markdown-max-size")?),
+        None => None,
+    };
+    options.delimiter = match args.value_of("delimiter").unwrap() {
+        "tab" | "\\t" => b'\t',
+        value if value.len() == 1 => value.as_bytes()[0],
+        value => return Err(format!("Invalid delimiter {}", value).into()),
+    };
     Ok(())
 }
 
@@ -37,28 +244,346 @@ fn command_args<'help>() -> Command<'help> {
     Command::new("scomp - Schema comparison for MySQL")
         .version("0.2.0")
         .author("Bruce Tsai")
-        .args(&[
-            Arg::new("config")
-                .short('c')
-                .long("config")
-                .takes_value(true)
-                .required(true)
-                .help("MySQL connection config file"),
-            Arg::new("diff-columns")
-                .long("column")
-                .takes_value(true)
-                .default_value("diff-columns.csv")
-                .help("Output file of columns comparison"),
-            Arg::new("diff-indices")
-                .long("indices")
-                .takes_value(true)
-                .default_value("diff-indices.csv")
-                .help("Output file of indices comparison"),
+        .subcommand_required(true)
+        .arg_required_else_help(true)
+        .arg(
             Arg::new("level")
                 .long("level")
                 .takes_value(true)
+                .global(true)
                 .default_value("info")
                 .help("Log level"),
-            Arg::new("source").long("source").help("Output source data"),
+        )
+        .subcommands([
+            Command::new("collect")
+                .about("Collect schema info of all databases into a snapshot")
+                .arg(config_arg().required(true))
+                .args(collect_args())
+                .arg(
+                    Arg::new("out")
+                        .short('o')
+                        .long("out")
+                        .takes_value(true)
+                        .required(true)
+                        .help("Output directory of the snapshot"),
+                ),
+            Command::new("diff")
+                .about("Compare databases or a snapshot, and write comparison outputs")
+                .args(source_args())
+                .args(collect_args())
+                .args(compare_args())
+                .args(output_args())
+                .args(report_args()),
+            Command::new("report")
+                .about("Render a diff saved by `diff --save` in another format")
+                .args([
+                    Arg::new("diff")
+                        .required(true)
+                        .help("Diff file saved by `diff --save`"),
+                    Arg::new("format")
+                        .short('f')
+                        .long("format")
+                        .takes_value(true)
+                        .required(true)
+                        .possible_values(REPORT_FORMATS)
+                        .help("Format of the report"),
+                    Arg::new("output")
+                        .short('o')
+                        .long("output")
+                        .takes_value(true)
+                        .required(true)
+                        .help("Output file of the report, or output directory of the csv files"),
+                ])
+                .args(report_args()),
+            Command::new("migrate")
+                .about("Write migration scripts towards the reference definition")
+                .args(source_args())
+                .args(collect_args())
+                .args(compare_args())
+                .arg(
+                    Arg::new("out")
+                        .short('o')
+                        .long("out")
+                        .takes_value(true)
+                        .required(true)
+                        .help("Output directory of migration scripts, one per deploy"),
+                ),
+            Command::new("check")
+                .about("Exit with 1 when differences are found, and 2 when databases can't be compared")
+                .args(source_args())
+                .args(collect_args())
+                .args(compare_args())
+                .arg(
+                    Arg::new("fail-on")
+                        .long("fail-on")
+                        .takes_value(true)
+                        .multiple_values(true)
+                        .use_value_delimiter(true)
+                        .possible_values(DriftCategory::ALL.map(|c| c.name()))
+                        .help("Categories of differences failing the check, others are only warned, comment needs \"comment\": true in the rules file [default: all]"),
+                ),
         ])
 }
+
+fn config_arg<'help>() -> Arg<'help> {
+    Arg::new("config")
+        .short('c')
+        .long("config")
+        .takes_value(true)
+        .help("MySQL connection config file")
+}
+
+/// Where compared schema info comes from, databases or a saved snapshot
+fn source_args<'help>() -> Vec<Arg<'help>> {
+    vec![
+        config_arg()
+            .required_unless_present("from-snapshot")
+            .conflicts_with("from-snapshot"),
+        Arg::new("from-snapshot")
+            .long("from-snapshot")
+            .takes_value(true)
+            .help("Compare a saved snapshot directory instead of connecting to databases"),
+    ]
+}
+
+fn collect_args<'help>() -> Vec<Arg<'help>> {
+    vec![
+        Arg::new("jobs")
+            .short('j')
+            .long("jobs")
+            .takes_value(true)
+            .default_value("4")
+            .help("Count of databases collected at the same time"),
+        Arg::new("continue-on-error")
+            .long("continue-on-error")
+            .help("Report unreachable databases and compare the others instead of aborting"),
+        Arg::new("retries")
+            .long("retries")
+            .takes_value(true)
+            .default_value("0")
+            .help("Retry count of each database after a failed collection"),
+        Arg::new("retry-delay")
+            .long("retry-delay")
+            .takes_value(true)
+            .default_value("1000")
+            .help("Delay in milliseconds before the first retry, doubled after each retry up to a minute"),
+        Arg::new("timeout")
+            .long("timeout")
+            .takes_value(true)
+            .help("Timeout in seconds of connecting and querying each database"),
+    ]
+}
+
+fn compare_args<'help>() -> Vec<Arg<'help>> {
+    vec![
+        Arg::new("baseline")
+            .long("baseline")
+            .takes_value(true)
+            .value_name("ENVIRONMENT:COUNTRY")
+            .help("Deploy compared with instead of the most used definition"),
+        Arg::new("rules")
+            .long("rules")
+            .takes_value(true)
+            .help("Rules file of the compared column attributes"),
+        Arg::new("ignore-default-collation")
+            .long("ignore-default-collation")
+            .help("Ignore column charset and collation which are the default of their table"),
+        Arg::new("ignore-partition-boundaries")
+            .long("ignore-partition-boundaries")
+            .help("Compare range and list partitioning by method and expression only, not partitions and bounds"),
+    ]
+}
+
+fn output_args<'help>() -> Vec<Arg<'help>> {
+    vec![
+        Arg::new("snapshot-out")
+            .long("snapshot-out")
+            .takes_value(true)
+            .conflicts_with("from-snapshot")
+            .help("Output directory of collected source data"),
+        Arg::new("save")
+            .long("save")
+            .takes_value(true)
+            .help("Output file of the whole comparison, rendered later by `report`"),
+        Arg::new("no-csv")
+            .long("no-csv")
+            .help("Write only the csv outputs given explicitly, instead of all of them by default"),
+        Arg::new("diff-tables")
+            .long("tables")
+            .takes_value(true)
+            .default_value("diff-tables.csv")
+            .help("Output file of tables comparison"),
+        Arg::new("missing-tables")
+            .long("missing-tables")
+            .takes_value(true)
+            .default_value("missing-tables.csv")
+            .help("Output file of tables missing in some deploys"),
+        Arg::new("diff-columns")
+            .long("column")
+            .takes_value(true)
+            .default_value("diff-columns.csv")
+            .help("Output file of columns comparison"),
+        Arg::new("diff-indices")
+            .long("indices")
+            .takes_value(true)
+            .default_value("diff-indices.csv")
+            .help("Output file of indices comparison"),
+        Arg::new("diff-foreign-keys")
+            .long("foreign-keys")
+            .takes_value(true)
+            .default_value("diff-foreign-keys.csv")
+            .help("Output file of foreign keys comparison"),
+        Arg::new("diff-partitions")
+            .long("partitions")
+            .takes_value(true)
+            .default_value("diff-partitions.csv")
+            .help("Output file of partitioning schemes comparison"),
+        Arg::new("diff-triggers")
+            .long("triggers")
+            .takes_value(true)
+            .default_value("diff-triggers.csv")
+            .help("Output file of triggers comparison"),
+        Arg::new("diff-views")
+            .long("views")
+            .takes_value(true)
+            .default_value("diff-views.csv")
+            .help("Output file of views comparison"),
+        Arg::new("diff-routines")
+            .long("routines")
+            .takes_value(true)
+            .default_value("diff-routines.csv")
+            .help("Output file of stored procedures and functions comparison"),
+        Arg::new("routine-diff")
+            .long("routine-diff")
+            .takes_value(true)
+            .help("Output file of unified diffs of differing routine definitions"),
+        Arg::new("diff-events")
+            .long("events")
+            .takes_value(true)
+            .default_value("diff-events.csv")
+            .help("Output file of scheduled events comparison"),
+        Arg::new("html")
+            .long("html")
+            .takes_value(true)
+            .help("Output file of a self-contained html report"),
+        Arg::new("json")
+            .long("json")
+            .takes_value(true)
+            .help("Output file of differing objects as json"),
+        Arg::new("json-lines")
+            .long("json-lines")
+            .takes_value(true)
+            .help("Output file of differing objects as json lines"),
+        Arg::new("xlsx")
+            .long("xlsx")
+            .takes_value(true)
+            .help("Output file of an excel workbook with one sheet per object type"),
+        Arg::new("markdown")
+            .long("markdown")
+            .takes_value(true)
+            .help("Output file of a markdown report of tables with differences"),
+        Arg::new("junit")
+            .long("junit")
+            .takes_value(true)
+            .help("Output file of a junit xml report with one test suite per table"),
+    ]
+}
+
+/// Options of rendered reports, shared by `diff` and `report`
+fn report_args<'help>() -> Vec<Arg<'help>> {
+    vec![
+        Arg::new("only-diff")
+            .long("only-diff")
+            .help("Leave out objects identical in all deploys, with a count of omitted objects"),
+        Arg::new("markdown-max-size")
+            .long("markdown-max-size")
+            .takes_value(true)
+            .help("Maximum size in bytes of the markdown report, further differences are left out"),
+        Arg::new("delimiter")
+            .long("delimiter")
+            .takes_value(true)
+            .default_value(",")
+            .help("Field delimiter of csv outputs, a single character or `tab`"),
+    ]
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn parse(args: &[&str]) -> ArgMatches {
+        command_args()
+            .try_get_matches_from(args)
+            .unwrap_or_else(|e| panic!("{:?}: {}", args, e))
+    }
+
+    #[test]
+    fn test_command_args() {
+        command_args().debug_assert();
+    }
+
+    #[test]
+    fn test_collect_args() {
+        let args = parse(&["scomp", "collect", "-c", "config.json", "-o", "snapshot"]);
+        let (name, args) = args.subcommand().unwrap();
+        assert_eq!(name, "collect");
+        assert_eq!(args.value_of("out"), Some("snapshot"));
+        assert_eq!(args.value_of_t::<usize>("jobs").unwrap(), 4);
+    }
+
+    #[test]
+    fn test_diff_args() {
+        let args = parse(&["scomp", "diff", "--from-snapshot", "snapshot", "--no-csv"]);
+        let (name, args) = args.subcommand().unwrap();
+        assert_eq!(name, "diff");
+        assert!(args.is_present("no-csv"));
+        assert_eq!(args.value_of("diff-columns"), Some("diff-columns.csv"));
+        assert_eq!(args.occurrences_of("diff-columns"), 0);
+
+        assert!(command_args()
+            .try_get_matches_from(["scomp", "diff", "-c", "config.json", "--from-snapshot", "s"])
+            .is_err());
+    }
+
+    #[test]
+    fn test_report_args() {
+        for format in REPORT_FORMATS {
+            let args = parse(&["scomp", "report", "diff.json", "-f", format, "-o", "out"]);
+            let (name, args) = args.subcommand().unwrap();
+            assert_eq!(name, "report");
+            assert_eq!(args.value_of("format"), Some(format));
+        }
+        assert!(command_args()
+            .try_get_matches_from(["scomp", "report", "diff.json", "-f", "pdf", "-o", "out"])
+            .is_err());
+    }
+
+    #[test]
+    fn test_migrate_args() {
+        let args = parse(&["scomp", "migrate", "-c", "config.json", "-o", "migration"]);
+        let (name, args) = args.subcommand().unwrap();
+        assert_eq!(name, "migrate");
+        assert_eq!(args.value_of("out"), Some("migration"));
+    }
+
+    #[test]
+    fn test_check_args() {
+        let args = parse(&[
+            "scomp",
+            "check",
+            "--from-snapshot",
+            "snapshot",
+            "--fail-on",
+            "missing-table,column",
+        ]);
+        let (name, args) = args.subcommand().unwrap();
+        assert_eq!(name, "check");
+        assert_eq!(
+            args.values_of("fail-on").unwrap().collect::<Vec<&str>>(),
+            vec!["missing-table", "column"]
+        );
+        assert!(command_args()
+            .try_get_matches_from(["scomp", "check", "--from-snapshot", "s", "--fail-on", "x"])
+            .is_err());
+    }
+}
@@ -1,21 +1,72 @@
 use serde::{Deserialize, Serialize};
+use std::time::Duration;
 
-#[derive(Debug, Deserialize, Serialize)]
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, PartialOrd, Deserialize, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Engine {
+    #[default]
+    Mysql,
+    Postgres,
+    Sqlite,
+}
+
+impl Engine {
+    pub fn scheme(&self) -> &'static str {
+        match self {
+            Engine::Mysql => "mysql",
+            Engine::Postgres => "postgres",
+            Engine::Sqlite => "sqlite",
+        }
+    }
+}
+
+#[derive(Debug, Clone, Deserialize, Serialize)]
 pub struct ConnectInfo {
+    #[serde(default)]
+    pub engine: Engine,
+    // host, port and user are not used by SQLite
+    #[serde(default)]
     pub host: String,
+    #[serde(default)]
     pub port: i32,
     pub schema: String,
+    #[serde(default)]
     pub user: String,
+    // never written to snapshots
+    #[serde(default, skip_serializing)]
     pub password: String,
     pub country: String,
     pub environment: String,
+    // database name of PostgreSQL (defaults to the user name), or database file of SQLite
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub database: Option<String>,
+    // every other deploy is compared with this one instead of the most used definition
+    #[serde(default)]
+    pub baseline: bool,
 }
 
 impl ConnectInfo {
-    pub fn to_deploy(&self) -> Deploy {
+    /// Connection url without credentials, for logging
+    pub fn display_url(&self) -> String {
+        if self.engine == Engine::Sqlite {
+            return format!("sqlite://{}", self.database.as_deref().unwrap_or_default());
+        }
+        format!(
+            "{}://{}:{}/{}",
+            self.engine.scheme(),
+            self.host,
+            self.port,
+            self.schema
+        )
+    }
+
+    pub fn to_deploy(&self) -> Deploy<'_> {
         Deploy {
             country: &self.country,
             environment: &self.environment,
+            engine: self.engine,
+            unreachable: false,
+            baseline: self.baseline,
         }
     }
 }
@@ -24,9 +75,225 @@ impl ConnectInfo {
 pub struct Deploy<'a> {
     pub country: &'a str,
     pub environment: &'a str,
+    // SQL dialect of collected definitions
+    pub engine: Engine,
+    // info of the deploy couldn't be collected
+    pub unreachable: bool,
+    // reference of the comparison
+    pub baseline: bool,
+}
+
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub struct CollectFailure {
+    pub country: String,
+    pub environment: String,
+    pub source: String,
+    pub error: String,
 }
 
-#[derive(Debug, PartialEq, PartialOrd, Eq, Serialize, Deserialize)]
+#[derive(Debug)]
+pub struct CollectOptions {
+    // count of databases collected at the same time
+    pub jobs: usize,
+    // report unreachable databases instead of aborting
+    pub continue_on_error: bool,
+    // attempts after the first failure of each database, the delay doubles after each attempt
+    pub retries: u32,
+    pub retry_delay: Duration,
+    pub timeout: Option<Duration>,
+}
+
+impl Default for CollectOptions {
+    fn default() -> Self {
+        CollectOptions {
+            jobs: 1,
+            continue_on_error: false,
+            retries: 0,
+            retry_delay: Duration::from_secs(1),
+            timeout: Option::None,
+        }
+    }
+}
+
+#[derive(Debug)]
+pub struct OutputOptions {
+    pub columns: Option<String>,
+    pub indices: Option<String>,
+    pub foreign_keys: Option<String>,
+    pub routines: Option<String>,
+    pub triggers: Option<String>,
+    pub views: Option<String>,
+    pub events: Option<String>,
+    pub partitions: Option<String>,
+    /// Unified diffs of routine definitions against the reference definition
+    pub routine_diff: Option<String>,
+    pub tables: Option<String>,
+    pub missing_tables: Option<String>,
+    pub snapshot: Option<String>,
+    pub migration: Option<String>,
+    pub html: Option<String>,
+    pub json: Option<String>,
+    pub json_lines: Option<String>,
+    pub xlsx: Option<String>,
+    pub markdown: Option<String>,
+    pub junit: Option<String>,
+    /// Directory of csv files rendered from a saved comparison, one per object type
+    pub csv: Option<String>,
+    /// File the comparison is saved to, for rendering reports later
+    pub diff: Option<String>,
+    /// Size in bytes the markdown report is capped at
+    pub markdown_max_size: Option<usize>,
+    /// Ignore column charset and collation when they are the default of their table
+    pub ignore_default_collation: bool,
+    /// Compare only partitioning method and expression, not partition names and bounds
+    pub ignore_partition_boundaries: bool,
+    /// Leave out objects identical in all deploys
+    pub only_diff: bool,
+    pub rules: CompareRules,
+    /// `<environment>:<country>` of the deploy to compare with, overrides the config
+    pub baseline: Option<String>,
+    /// Field delimiter of the csv outputs
+    pub delimiter: u8,
+    /// Count differences by category, returned by the comparison for the drift check
+    pub check: bool,
+}
+
+impl Default for OutputOptions {
+    fn default() -> Self {
+        OutputOptions {
+            columns: Option::None,
+            indices: Option::None,
+            foreign_keys: Option::None,
+            routines: Option::None,
+            triggers: Option::None,
+            views: Option::None,
+            events: Option::None,
+            partitions: Option::None,
+            routine_diff: Option::None,
+            tables: Option::None,
+            missing_tables: Option::None,
+            snapshot: Option::None,
+            migration: Option::None,
+            html: Option::None,
+            json: Option::None,
+            json_lines: Option::None,
+            xlsx: Option::None,
+            markdown: Option::None,
+            junit: Option::None,
+            csv: Option::None,
+            diff: Option::None,
+            markdown_max_size: Option::None,
+            ignore_default_collation: false,
+            ignore_partition_boundaries: false,
+            only_diff: false,
+            rules: CompareRules::default(),
+            baseline: Option::None,
+            delimiter: b',',
+            check: false,
+        }
+    }
+}
+
+/// Rules of what is compared, loaded from the rules file
+#[derive(Debug, Clone, Default, Deserialize, Serialize)]
+#[serde(default)]
+pub struct CompareRules {
+    pub columns: ColumnRules,
+}
+
+/// Attributes of a column definition taking part in the comparison, and equivalents to normalise
+#[derive(Debug, Clone, Deserialize, Serialize)]
+#[serde(default)]
+pub struct ColumnRules {
+    pub column_type: bool,
+    pub charset: bool,
+    pub nullable: bool,
+    pub default_value: bool,
+    pub extra: bool,
+    pub primary_key: bool,
+    pub comment: bool,
+    pub ordinal_position: bool,
+    /// Treat `int(11)` as `int`, MySQL 8 doesn't report the display width of integer types
+    pub ignore_integer_width: bool,
+    /// Treat a `NULL` default as no default
+    pub null_default_as_none: bool,
+}
+
+impl Default for ColumnRules {
+    fn default() -> Self {
+        ColumnRules {
+            column_type: true,
+            charset: true,
+            nullable: true,
+            default_value: true,
+            extra: false,
+            primary_key: false,
+            comment: false,
+            ordinal_position: false,
+            ignore_integer_width: false,
+            null_default_as_none: false,
+        }
+    }
+}
+
+#[derive(Debug, Default)]
+pub struct Snapshot {
+    pub sources: Vec<ConnectInfo>,
+    pub tables: Vec<TableInfo>,
+    pub columns: Vec<ColumnInfo>,
+    pub indices: Vec<IndexInfo>,
+    pub foreign_keys: Vec<ForeignKeyInfo>,
+    pub triggers: Vec<TriggerInfo>,
+    pub views: Vec<ViewInfo>,
+    pub routines: Vec<RoutineInfo>,
+    pub events: Vec<EventInfo>,
+    pub partitions: Vec<PartitionInfo>,
+    pub failures: Vec<CollectFailure>,
+}
+
+#[derive(Debug, Deserialize, Serialize)]
+pub struct SnapshotManifest {
+    pub version: u32,
+    pub created_at: String,
+    pub sources: Vec<ConnectInfo>,
+    #[serde(default)]
+    pub failures: Vec<CollectFailure>,
+}
+
+#[derive(Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct TableInfo {
+    pub country: String,
+    pub environment: String,
+    pub schema: String,
+
+    pub table: String,
+    pub engine: Option<String>,
+    pub collation: Option<String>,
+    pub row_format: Option<String>,
+    pub auto_increment: bool,
+    pub comment: Option<String>,
+
+    pub display: Option<String>,
+}
+
+impl PartialOrd for TableInfo {
+    fn partial_cmp(&self, other: &Self) -> Option<std::cmp::Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for TableInfo {
+    fn cmp(&self, other: &Self) -> std::cmp::Ordering {
+        (&self.schema, &self.table, &self.environment, &self.country).cmp(&(
+            &other.schema,
+            &other.table,
+            &other.environment,
+            &other.country,
+        ))
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
 pub struct ColumnInfo {
     pub country: String,
     pub environment: String,
@@ -40,13 +307,26 @@ pub struct ColumnInfo {
     pub text_octet_length: Option<i32>,
     pub num_precision: Option<i32>,
     pub num_scale: Option<i32>,
+    pub charset: Option<String>,
+    pub collation: Option<String>,
     pub default_value: Option<String>,
     pub nullable: String,
     pub comment: Option<String>,
+    pub extra: Option<String>,
+    #[serde(default)]
+    pub ordinal_position: i32,
+    #[serde(default)]
+    pub primary_key: bool,
 
     pub display: Option<String>,
 }
 
+impl PartialOrd for ColumnInfo {
+    fn partial_cmp(&self, other: &Self) -> Option<std::cmp::Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
 impl Ord for ColumnInfo {
     fn cmp(&self, other: &Self) -> std::cmp::Ordering {
         (
@@ -66,7 +346,7 @@ impl Ord for ColumnInfo {
     }
 }
 
-#[derive(Debug, PartialEq, Eq, PartialOrd, Serialize, Deserialize)]
+#[derive(Debug, PartialEq, Eq, Serialize, Deserialize)]
 pub struct IndexInfo {
     pub country: String,
     pub environment: String,
@@ -81,6 +361,12 @@ pub struct IndexInfo {
     pub display: Option<String>,
 }
 
+impl PartialOrd for IndexInfo {
+    fn partial_cmp(&self, other: &Self) -> Option<std::cmp::Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
 impl Ord for IndexInfo {
     fn cmp(&self, other: &Self) -> std::cmp::Ordering {
         (
@@ -100,6 +386,168 @@ impl Ord for IndexInfo {
     }
 }
 
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ForeignKeyInfo {
+    pub country: String,
+    pub environment: String,
+    pub schema: String,
+
+    pub table: String,
+    pub constraint_name: String,
+    pub sequence: i32,
+    pub column: String,
+    // empty in snapshots taken before the referenced schema was collected
+    #[serde(default)]
+    pub referenced_schema: String,
+    pub referenced_table: String,
+    pub referenced_column: String,
+    pub update_rule: String,
+    pub delete_rule: String,
+
+    pub display: Option<String>,
+}
+
+impl PartialOrd for ForeignKeyInfo {
+    fn partial_cmp(&self, other: &Self) -> Option<std::cmp::Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for ForeignKeyInfo {
+    fn cmp(&self, other: &Self) -> std::cmp::Ordering {
+        (
+            &self.schema,
+            &self.table,
+            &self.constraint_name,
+            &self.environment,
+            &self.country,
+            &self.sequence,
+        )
+            .cmp(&(
+                &other.schema,
+                &other.table,
+                &other.constraint_name,
+                &other.environment,
+                &other.country,
+                &other.sequence,
+            ))
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct RoutineInfo {
+    pub country: String,
+    pub environment: String,
+    pub schema: String,
+
+    // `PROCEDURE` or `FUNCTION`
+    pub routine_type: String,
+    pub routine_name: String,
+    // parameters with their mode, name and type
+    pub parameters: String,
+    // return type of functions
+    pub returns: Option<String>,
+    // create statement as the database reports it
+    pub definition: String,
+
+    pub display: Option<String>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct TriggerInfo {
+    pub country: String,
+    pub environment: String,
+    pub schema: String,
+
+    pub table: String,
+    pub trigger_name: String,
+    // `BEFORE`, `AFTER` or `INSTEAD OF`
+    pub timing: String,
+    // `INSERT`, `UPDATE` or `DELETE`, joined by `OR` when the trigger fires on several events
+    pub event: String,
+    pub statement: String,
+    // account the trigger runs as, MySQL only
+    pub definer: Option<String>,
+
+    pub display: Option<String>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ViewInfo {
+    pub country: String,
+    pub environment: String,
+    pub schema: String,
+
+    pub view_name: String,
+    // column names in order, joined by `, `
+    pub columns: String,
+    // select statement as the database reports it
+    pub definition: String,
+    // `NONE`, `LOCAL` or `CASCADED`
+    pub check_option: String,
+    // `DEFINER` or `INVOKER`, MySQL only
+    pub security_type: Option<String>,
+    pub updatable: bool,
+
+    pub display: Option<String>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct EventInfo {
+    pub country: String,
+    pub environment: String,
+    pub schema: String,
+
+    pub event_name: String,
+    // `AT <time>` or `EVERY <interval> STARTS <time>[ ENDS <time>]`
+    pub schedule: String,
+    // time zone of the schedule, empty in snapshots taken before it was collected
+    #[serde(default)]
+    pub time_zone: String,
+    // `ENABLED`, `DISABLED` or `SLAVESIDE_DISABLED`
+    pub status: String,
+    // `PRESERVE` or `NOT PRESERVE`
+    pub on_completion: String,
+    pub body: String,
+
+    pub display: Option<String>,
+}
+
+/// One partition of a partitioned table
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct PartitionInfo {
+    pub country: String,
+    pub environment: String,
+    pub schema: String,
+
+    pub table: String,
+    pub partition_name: String,
+    pub ordinal_position: i32,
+    // `RANGE`, `RANGE COLUMNS`, `LIST`, `LIST COLUMNS`, `HASH` or `KEY`
+    pub method: String,
+    pub expression: Option<String>,
+    // `VALUES LESS THAN (...)`, `VALUES IN (...)` or `FOR VALUES ...`, none for hash partitions
+    pub bound: Option<String>,
+    pub subpartition_method: Option<String>,
+    pub subpartition_expression: Option<String>,
+    // number of subpartitions of this partition
+    pub subpartitions: i32,
+
+    pub display: Option<String>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd)]
+pub struct UniqueTable<'a> {
+    pub schema: &'a str,
+    pub table: &'a str,
+}
+
+impl Ord for UniqueTable<'_> {
+    fn cmp(&self, other: &Self) -> std::cmp::Ordering {
+        (&self.schema, &self.table).cmp(&(&other.schema, &other.table))
+    }
+}
+
 #[derive(Debug, Clone, PartialEq, Eq, PartialOrd)]
 pub struct UniqueColumn<'a> {
     pub schema: &'a str,
@@ -133,3 +581,305 @@ impl Ord for UniqueIndex<'_> {
         ))
     }
 }
+
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd)]
+pub struct UniqueForeignKey<'a> {
+    pub schema: &'a str,
+    pub table: &'a str,
+    pub constraint_name: &'a str,
+}
+
+impl Ord for UniqueForeignKey<'_> {
+    fn cmp(&self, other: &Self) -> std::cmp::Ordering {
+        (&self.schema, &self.table, &self.constraint_name).cmp(&(
+            &other.schema,
+            &other.table,
+            &other.constraint_name,
+        ))
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd)]
+pub struct UniqueTrigger<'a> {
+    pub schema: &'a str,
+    pub table: &'a str,
+    pub trigger_name: &'a str,
+}
+
+impl Ord for UniqueTrigger<'_> {
+    fn cmp(&self, other: &Self) -> std::cmp::Ordering {
+        (&self.schema, &self.table, &self.trigger_name).cmp(&(
+            &other.schema,
+            &other.table,
+            &other.trigger_name,
+        ))
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord)]
+pub struct UniqueView<'a> {
+    pub schema: &'a str,
+    pub view_name: &'a str,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord)]
+pub struct UniqueRoutine<'a> {
+    pub schema: &'a str,
+    pub routine_name: &'a str,
+    // procedures and functions have names of their own in MySQL
+    pub routine_type: &'a str,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord)]
+pub struct UniqueEvent<'a> {
+    pub schema: &'a str,
+    pub event_name: &'a str,
+}
+
+/// Kind of compared object
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ObjectKind {
+    Table,
+    Column,
+    Index,
+    ForeignKey,
+    Partition,
+    Trigger,
+    View,
+    Routine,
+    Event,
+}
+
+impl ObjectKind {
+    pub const ALL: [ObjectKind; 9] = [
+        ObjectKind::Table,
+        ObjectKind::Column,
+        ObjectKind::Index,
+        ObjectKind::ForeignKey,
+        ObjectKind::Partition,
+        ObjectKind::Trigger,
+        ObjectKind::View,
+        ObjectKind::Routine,
+        ObjectKind::Event,
+    ];
+
+    pub fn label(&self) -> &'static str {
+        match self {
+            ObjectKind::Table => "Table",
+            ObjectKind::Column => "Column",
+            ObjectKind::Index => "Index",
+            ObjectKind::ForeignKey => "Foreign Key",
+            ObjectKind::Partition => "Partitioning",
+            ObjectKind::Trigger => "Trigger",
+            ObjectKind::View => "View",
+            ObjectKind::Routine => "Routine",
+            ObjectKind::Event => "Event",
+        }
+    }
+
+    /// Objects of tables, as opposed to objects of the schema like views, routines and events
+    pub fn in_table(&self) -> bool {
+        !matches!(
+            self,
+            ObjectKind::View | ObjectKind::Routine | ObjectKind::Event
+        )
+    }
+
+    /// Objects with a name of their own, as opposed to table options and partitioning which are
+    /// identified by their table
+    pub fn named(&self) -> bool {
+        !matches!(self, ObjectKind::Table | ObjectKind::Partition)
+    }
+}
+
+/// Comparison of one object across all deploys
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DiffRow {
+    pub kind: ObjectKind,
+    pub schema: String,
+    // empty for objects of the schema, like views, routines and events
+    pub table: String,
+    // column, index, foreign key, trigger, view, routine or event name, empty for table options
+    // and partitioning
+    pub name: String,
+    // `PROCEDURE` or `FUNCTION` of routines, which may share a name, empty for other objects
+    #[serde(default, skip_serializing_if = "String::is_empty")]
+    pub routine_type: String,
+    // definition every deploy is compared with, the baseline or the most used one
+    pub reference: String,
+    // one cell per deploy, in the order of deploys
+    pub cells: Vec<DiffCell>,
+}
+
+impl DiffRow {
+    /// Any reachable deploy differs from the reference
+    pub fn differs(&self) -> bool {
+        self.cells.iter().any(|c| c.differs)
+    }
+
+    /// Name of the object in reports, with the type of routines
+    pub fn display_name(&self) -> String {
+        if self.routine_type.is_empty() {
+            self.name.clone()
+        } else {
+            format!("{} ({})", self.name, self.routine_type)
+        }
+    }
+}
+
+/// Comparison saved by [`save_diff`](crate::compare_mysql::save_diff), to render reports later
+#[derive(Debug, Serialize, Deserialize)]
+pub struct SavedDiff {
+    pub version: u32,
+    pub created_at: String,
+    pub deploys: Vec<SavedDeploy>,
+    pub rows: Vec<DiffRow>,
+}
+
+/// Owned copy of a [`Deploy`] in a saved diff
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SavedDeploy {
+    pub environment: String,
+    pub country: String,
+    #[serde(default)]
+    pub engine: Engine,
+    pub unreachable: bool,
+    pub baseline: bool,
+}
+
+/// Definition of an object in one deploy, empty when the object doesn't exist
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DiffCell {
+    pub value: String,
+    pub differs: bool,
+    pub unreachable: bool,
+}
+
+impl DiffCell {
+    pub fn new(value: String, reference: &str) -> Self {
+        DiffCell {
+            differs: value != reference,
+            value,
+            unreachable: false,
+        }
+    }
+
+    pub fn unreachable() -> Self {
+        DiffCell {
+            value: String::new(),
+            differs: false,
+            unreachable: true,
+        }
+    }
+}
+
+/// Kind of difference found by a check, objects missing from a deploy are told apart from changed
+/// definitions
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum DriftCategory {
+    MissingTable,
+    MissingColumn,
+    MissingIndex,
+    MissingForeignKey,
+    MissingTrigger,
+    MissingView,
+    MissingRoutine,
+    MissingEvent,
+    Table,
+    Column,
+    // columns which differ only in comment
+    Comment,
+    Index,
+    ForeignKey,
+    // partitioning scheme, including tables partitioned in some deploys only
+    Partition,
+    Trigger,
+    View,
+    Routine,
+    Event,
+}
+
+impl DriftCategory {
+    pub const ALL: [DriftCategory; 18] = [
+        DriftCategory::MissingTable,
+        DriftCategory::MissingColumn,
+        DriftCategory::MissingIndex,
+        DriftCategory::MissingForeignKey,
+        DriftCategory::MissingTrigger,
+        DriftCategory::MissingView,
+        DriftCategory::MissingRoutine,
+        DriftCategory::MissingEvent,
+        DriftCategory::Table,
+        DriftCategory::Column,
+        DriftCategory::Comment,
+        DriftCategory::Index,
+        DriftCategory::ForeignKey,
+        DriftCategory::Partition,
+        DriftCategory::Trigger,
+        DriftCategory::View,
+        DriftCategory::Routine,
+        DriftCategory::Event,
+    ];
+
+    /// Name used on the command line
+    pub fn name(&self) -> &'static str {
+        match self {
+            DriftCategory::MissingTable => "missing-table",
+            DriftCategory::MissingColumn => "missing-column",
+            DriftCategory::MissingIndex => "missing-index",
+            DriftCategory::MissingForeignKey => "missing-foreign-key",
+            DriftCategory::MissingTrigger => "missing-trigger",
+            DriftCategory::MissingView => "missing-view",
+            DriftCategory::MissingRoutine => "missing-routine",
+            DriftCategory::MissingEvent => "missing-event",
+            DriftCategory::Table => "table",
+            DriftCategory::Column => "column",
+            DriftCategory::Comment => "comment",
+            DriftCategory::Index => "index",
+            DriftCategory::ForeignKey => "foreign-key",
+            DriftCategory::Partition => "partition",
+            DriftCategory::Trigger => "trigger",
+            DriftCategory::View => "view",
+            DriftCategory::Routine => "routine",
+            DriftCategory::Event => "event",
+        }
+    }
+}
+
+impl std::str::FromStr for DriftCategory {
+    type Err = String;
+
+    fn from_str(value: &str) -> std::result::Result<Self, Self::Err> {
+        DriftCategory::ALL
+            .into_iter()
+            .find(|c| c.name() == value)
+            .ok_or_else(|| {
+                format!(
+                    "Unknown drift category {}, expected one of {}",
+                    value,
+                    DriftCategory::ALL.map(|c| c.name()).join(", ")
+                )
+            })
+    }
+}
+
+/// Differences found by a comparison, for deciding whether a check passes
+#[derive(Debug, Clone, Default)]
+pub struct Drift {
+    // count of differing objects of each category
+    pub counts: std::collections::BTreeMap<DriftCategory, usize>,
+    // count of deploys whose schema couldn't be collected
+    pub unreachable: usize,
+}
+
+impl Drift {
+    /// Count of differing objects in `categories`
+    pub fn count(&self, categories: &[DriftCategory]) -> usize {
+        self.counts
+            .iter()
+            .filter(|(category, _)| categories.contains(category))
+            .map(|(_, count)| count)
+            .sum()
+    }
+}
@@ -0,0 +1,89 @@
+use scomp::compare_mysql::{compare, load_snapshot};
+use scomp::value_object::{CollectFailure, DriftCategory, OutputOptions, Result};
+
+fn options(name: &str) -> OutputOptions {
+    OutputOptions {
+        columns: Some(format!("target/test-output/check/{}-columns.csv", name)),
+        indices: Some(format!("target/test-output/check/{}-indices.csv", name)),
+        foreign_keys: Some(format!(
+            "target/test-output/check/{}-foreign-keys.csv",
+            name
+        )),
+        check: true,
+        ..Default::default()
+    }
+}
+
+#[test]
+fn test_drift() -> Result<()> {
+    let snapshot = load_snapshot("tests/resources/snapshot")?;
+    let drift = compare(&snapshot, &options("drift"))?.unwrap();
+    assert_eq!(drift.unreachable, 0);
+    assert_eq!(drift.counts.get(&DriftCategory::MissingColumn), Some(&4));
+    assert_eq!(drift.counts.get(&DriftCategory::MissingIndex), Some(&3));
+    assert_eq!(drift.counts.get(&DriftCategory::Column), Some(&2));
+    assert_eq!(drift.counts.get(&DriftCategory::Comment), None);
+    assert_eq!(
+        drift.count(&[DriftCategory::MissingColumn, DriftCategory::MissingIndex]),
+        7
+    );
+    Ok(())
+}
+
+#[test]
+fn test_drift_comment() -> Result<()> {
+    let mut snapshot = load_snapshot("tests/resources/snapshot")?;
+    for column in snapshot
+        .columns
+        .iter_mut()
+        .filter(|c| c.table == "customer" && c.column_name == "name" && c.country == "tz")
+    {
+        column.comment = Some("display name".to_string());
+    }
+
+    // comments aren't compared by default
+    let drift = compare(&snapshot, &options("comment"))?.unwrap();
+    assert_eq!(drift.counts.get(&DriftCategory::Comment), None);
+
+    let mut options = options("comment");
+    options.rules.columns.comment = true;
+    let drift = compare(&snapshot, &options)?.unwrap();
+    assert_eq!(drift.counts.get(&DriftCategory::Comment), Some(&1));
+    assert_eq!(drift.counts.get(&DriftCategory::Column), Some(&2));
+    assert_eq!(drift.count(&[DriftCategory::Comment]), 1);
+    Ok(())
+}
+
+#[test]
+fn test_drift_unreachable() -> Result<()> {
+    let mut snapshot = load_snapshot("tests/resources/snapshot")?;
+    snapshot.failures.push(CollectFailure {
+        environment: "uat".to_string(),
+        country: "ug".to_string(),
+        source: "uat-ug".to_string(),
+        error: "Connection refused".to_string(),
+    });
+    let drift = compare(&snapshot, &options("unreachable"))?.unwrap();
+    assert_eq!(drift.unreachable, 1);
+    Ok(())
+}
+
+#[test]
+fn test_csv_only() -> Result<()> {
+    let snapshot = load_snapshot("tests/resources/snapshot")?;
+    let options = OutputOptions {
+        check: false,
+        ..options("csv-only")
+    };
+    assert!(compare(&snapshot, &options)?.is_none());
+    Ok(())
+}
+
+#[test]
+fn test_drift_category() {
+    assert_eq!(
+        "missing-column".parse::<DriftCategory>(),
+        Ok(DriftCategory::MissingColumn)
+    );
+    assert!("columns".parse::<DriftCategory>().is_err());
+}
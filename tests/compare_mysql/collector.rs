@@ -1,8 +1,16 @@
 use std::fs::read_to_string;
 
-use scomp::compare_mysql::{build_deploy, output_column_info, output_index_info, read_config, start};
+use scomp::compare_mysql::{
+    build_deploy, load_diff, output_column_info, output_foreign_key_info, output_index_info,
+    read_config, read_rules, report, set_baseline, start, strip_default_collation,
+};
 use scomp::init_log;
-use scomp::value_object::Result;
+
+use super::sqlite::prepare_databases;
+use scomp::value_object::{
+    CollectOptions, ColumnInfo, ColumnRules, ForeignKeyInfo, IndexInfo, OutputOptions, Result,
+    TableInfo,
+};
 
 static INIT: std::sync::Once = std::sync::Once::new();
 
@@ -11,13 +19,56 @@ fn test_start() -> Result<()> {
     INIT.call_once(|| {
         init_log("Trace").unwrap();
     });
-    let config_file = "datasource3.json";
-    let columns_output_file = "diff-columns.csv";
-    let indice_output_file = "diff-indices.csv";
-    let source = false;
-    let configs = read_config(config_file)?;
+    let configs = prepare_databases()?;
+    let options = OutputOptions {
+        columns: Some("target/test-output/start/diff-columns.csv".to_string()),
+        indices: Some("target/test-output/start/diff-indices.csv".to_string()),
+        foreign_keys: Some("target/test-output/start/diff-foreign-keys.csv".to_string()),
+        tables: Some("target/test-output/start/diff-tables.csv".to_string()),
+        missing_tables: Some("target/test-output/start/missing-tables.csv".to_string()),
+        html: Some("target/test-output/start/report.html".to_string()),
+        diff: Some("target/test-output/start/diff.json".to_string()),
+        ..Default::default()
+    };
+
+    start(
+        &configs,
+        &CollectOptions {
+            jobs: 2,
+            ..Default::default()
+        },
+        &options,
+    )?;
 
-    start(&configs, source, columns_output_file, indice_output_file)?;
+    let tables = read_to_string(options.tables.as_ref().unwrap())?;
+    assert!(tables.contains("main,order,,**MISSING,\n"));
+    let missing_tables = read_to_string(options.missing_tables.as_ref().unwrap())?;
+    assert_eq!(
+        missing_tables,
+        "Schema,Table,Missing,Existing\nmain,order,[uat tz],\"[uat ke], [uat ug]\"\n"
+    );
+    let columns = read_to_string(options.columns.as_ref().unwrap())?;
+    assert!(columns.contains("main,customer,email,**varchar(64) NULL,varchar(128) NULL"));
+    // reported as a missing table instead
+    assert!(!columns.contains("main,order,"));
+    let indices = read_to_string(options.indices.as_ref().unwrap())?;
+    assert!(indices.contains("main,customer,idx_customer_legacy,,,**legacy_code"));
+    let foreign_keys = read_to_string(options.foreign_keys.as_ref().unwrap())?;
+    assert!(foreign_keys.contains("main,order,fk_order_customer_id,"));
+    let html = read_to_string(options.html.as_ref().unwrap())?;
+    assert!(html.contains("<h3>main.order</h3>"));
+
+    // the missing tables are derived again from a saved diff
+    let diff = load_diff(options.diff.as_ref().unwrap())?;
+    let rendered = OutputOptions {
+        csv: Some("target/test-output/start/csv".to_string()),
+        ..Default::default()
+    };
+    report(&diff, &rendered)?;
+    assert_eq!(
+        read_to_string("target/test-output/start/csv/missing-tables.csv")?,
+        missing_tables
+    );
 
     Ok(())
 }
@@ -27,11 +78,179 @@ fn test_output_column_info() -> Result<()> {
     INIT.call_once(|| {
         init_log("Info").unwrap();
     });
-    let configs = read_config("datasource3.json")?;
+    let configs = read_config("tests/resources/datasource.json")?;
+    let deploys = build_deploy(&configs)?;
+    let columns_json = &read_to_string("tests/resources/snapshot/columns.json")?;
+    let columns: Vec<ColumnInfo> = serde_json::from_str(columns_json)?;
+    output_column_info(
+        "target/test-output/diff-columns.csv",
+        &columns,
+        &[],
+        &deploys,
+        &ColumnRules::default(),
+        b'\t',
+        false,
+    )?;
+    Ok(())
+}
+
+#[test]
+fn test_baseline() -> Result<()> {
+    INIT.call_once(|| {
+        init_log("Info").unwrap();
+    });
+    let mut configs = read_config("tests/resources/datasource.json")?;
+    let columns_json = &read_to_string("tests/resources/snapshot/columns.json")?;
+    let columns: Vec<ColumnInfo> = serde_json::from_str(columns_json)?;
+    let output_file = "target/test-output/diff-columns-baseline.csv";
+    let rules = ColumnRules::default();
+
+    // ties are broken by deploy order
+    let mut deploys = build_deploy(&configs)?;
+    deploys[2].unreachable = true;
+    output_column_info(output_file, &columns, &[], &deploys, &rules, b'\t', false)?;
+    let output = read_to_string(output_file)?;
+    assert!(output
+        .contains("main\tcustomer\temail\tvarchar(64) NULL\t**varchar(128) NULL\tUNREACHABLE\n"));
+
+    let mut deploys = build_deploy(&configs)?;
+    set_baseline(&mut deploys, "uat:ke")?;
+    output_column_info(output_file, &columns, &[], &deploys, &rules, b'\t', false)?;
+    let output = read_to_string(output_file)?;
+    assert!(output.contains(
+        "main\tcustomer\temail\tvarchar(64) NULL\t**varchar(128) NULL\t**varchar(128) NULL\n"
+    ));
+    assert!(set_baseline(&mut deploys, "uat:xx").is_err());
+    assert!(set_baseline(&mut deploys, "uat").is_err());
+
+    configs[0].baseline = true;
+    configs[1].baseline = true;
+    assert!(build_deploy(&configs).is_err());
+
+    Ok(())
+}
+
+#[test]
+fn test_column_rules() -> Result<()> {
+    INIT.call_once(|| {
+        init_log("Info").unwrap();
+    });
+    let configs = read_config("tests/resources/datasource.json")?;
+    let deploys = build_deploy(&configs)?;
+    let columns_json = &read_to_string("tests/resources/snapshot/columns.json")?;
+    let mut columns: Vec<ColumnInfo> = serde_json::from_str(columns_json)?;
+    for column in columns.iter_mut().filter(|c| c.table == "customer") {
+        match (column.country.as_str(), column.column_name.as_str()) {
+            ("ke", "status") => column.column_type = "tinyint(4)".to_string(),
+            ("ug", "name") => column.default_value = Some("NULL".to_string()),
+            ("tz", "id") => column.comment = Some("customer \"id\"".to_string()),
+            _ => {}
+        }
+    }
+
+    let output_file = "target/test-output/diff-columns-rules.csv";
+    output_column_info(
+        output_file,
+        &columns,
+        &[],
+        &deploys,
+        &ColumnRules::default(),
+        b'\t',
+        false,
+    )?;
+    let output = read_to_string(output_file)?;
+    assert!(output.contains("main\tcustomer\tid\tbigint\tbigint\tbigint\n"));
+    assert!(output.contains("main\tcustomer\tname\tvarchar(64)\tvarchar(64)\t**varchar(64) NULL\n"));
+    assert!(output.contains("main\tcustomer\tstatus\t**tinyint(4) 1\ttinyint 1\ttinyint 1\n"));
+
+    let rules = read_rules("tests/resources/rules.json")?;
+    output_column_info(
+        output_file,
+        &columns,
+        &[],
+        &deploys,
+        &rules.columns,
+        b'\t',
+        false,
+    )?;
+    let output = read_to_string(output_file)?;
+    assert!(output.contains(
+        "main\tcustomer\tid\tbigint\t\"**bigint COMMENT 'customer \"\"id\"\"'\"\tbigint\n"
+    ));
+    assert!(output.contains("main\tcustomer\tname\tvarchar(64)\tvarchar(64)\tvarchar(64)\n"));
+    assert!(output.contains("main\tcustomer\tstatus\ttinyint 1 COMMENT '1: active, 0: inactive'\t"));
+
+    Ok(())
+}
+
+#[test]
+fn test_strip_default_collation() -> Result<()> {
+    INIT.call_once(|| {
+        init_log("Info").unwrap();
+    });
+    let configs = read_config("tests/resources/datasource.json")?;
     let deploys = build_deploy(&configs)?;
-    let columns_json = &read_to_string("columns.json")?;
-    let mut columns = serde_json::from_str(columns_json)?;
-    output_column_info("test-diff-columns.csv", &mut columns, &deploys)?;
+    let columns_json = &read_to_string("tests/resources/snapshot/columns.json")?;
+    let mut columns: Vec<ColumnInfo> = serde_json::from_str(columns_json)?;
+    let mut tables: Vec<TableInfo> = vec![];
+    for deploy in &deploys {
+        let charset = if deploy.country == "tz" {
+            "utf8"
+        } else {
+            "utf8mb4"
+        };
+        let collation = format!("{}_general_ci", charset);
+        for column in columns
+            .iter_mut()
+            .filter(|c| c.country == deploy.country && c.column_name == "name")
+        {
+            column.charset = Some(charset.to_string());
+            column.collation = Some(collation.clone());
+        }
+        tables.push(TableInfo {
+            country: deploy.country.to_string(),
+            environment: deploy.environment.to_string(),
+            schema: "main".to_string(),
+            table: "customer".to_string(),
+            engine: Some("InnoDB".to_string()),
+            collation: Some(collation),
+            row_format: Some("Dynamic".to_string()),
+            auto_increment: false,
+            comment: Some(String::new()),
+            display: None,
+        });
+    }
+
+    let output_file = "target/test-output/diff-columns-collation.csv";
+    output_column_info(
+        output_file,
+        &columns,
+        &[],
+        &deploys,
+        &ColumnRules::default(),
+        b'\t',
+        false,
+    )?;
+    let output = read_to_string(output_file)?;
+    assert!(output.contains(
+        "main\tcustomer\tname\tvarchar(64) CHARACTER SET utf8mb4 COLLATE utf8mb4_general_ci\t\
+         **varchar(64) CHARACTER SET utf8 COLLATE utf8_general_ci\t\
+         varchar(64) CHARACTER SET utf8mb4 COLLATE utf8mb4_general_ci\n"
+    ));
+
+    let stripped = strip_default_collation(&columns, &tables);
+    output_column_info(
+        output_file,
+        &stripped,
+        &[],
+        &deploys,
+        &ColumnRules::default(),
+        b'\t',
+        false,
+    )?;
+    let output = read_to_string(output_file)?;
+    assert!(output.contains("main\tcustomer\tname\tvarchar(64)\tvarchar(64)\tvarchar(64)\n"));
+
     Ok(())
 }
 
@@ -40,10 +259,100 @@ fn test_output_index_info() -> Result<()> {
     INIT.call_once(|| {
         init_log("Info").unwrap();
     });
-    let configs = read_config("datasource2.json")?;
+    let configs = read_config("tests/resources/datasource.json")?;
+    let deploys = build_deploy(&configs)?;
+    let indices_json = &read_to_string("tests/resources/snapshot/indices.json")?;
+    let indices: Vec<IndexInfo> = serde_json::from_str(indices_json)?;
+    output_index_info(
+        "target/test-output/diff-indices.csv",
+        &indices,
+        &[],
+        &deploys,
+        b'\t',
+        false,
+    )?;
+    Ok(())
+}
+
+#[test]
+fn test_output_foreign_key_info() -> Result<()> {
+    INIT.call_once(|| {
+        init_log("Info").unwrap();
+    });
+    let configs = read_config("tests/resources/datasource.json")?;
+    let deploys = build_deploy(&configs)?;
+    let foreign_keys_json = &read_to_string("tests/resources/snapshot/foreign-keys.json")?;
+    let foreign_keys: Vec<ForeignKeyInfo> = serde_json::from_str(foreign_keys_json)?;
+    let output_file = "target/test-output/diff-foreign-keys.csv";
+    output_foreign_key_info(output_file, &foreign_keys, &[], &deploys, b'\t', false)?;
+
+    let output = read_to_string(output_file)?;
+    let lines: Vec<&str> = output.lines().collect();
+    assert_eq!(
+        lines[0],
+        "Schema\tTable\tForeign Key\t[uat ke]\t[uat tz]\t[uat ug]"
+    );
+    assert_eq!(
+        lines[1],
+        "main\torder_item\tfk_order_item_order\
+        \t(order_id) REFERENCES order(id) ON UPDATE RESTRICT ON DELETE CASCADE\
+        \t(order_id) REFERENCES order(id) ON UPDATE RESTRICT ON DELETE CASCADE\
+        \t(order_id) REFERENCES order(id) ON UPDATE RESTRICT ON DELETE CASCADE"
+    );
+    assert_eq!(
+        lines[2],
+        "main\torder_item\tfk_order_item_product\
+        \t(product_id) REFERENCES product(id) ON UPDATE RESTRICT ON DELETE CASCADE\
+        \t(product_id) REFERENCES product(id) ON UPDATE RESTRICT ON DELETE CASCADE\
+        \t**(product_id) REFERENCES product(id) ON UPDATE RESTRICT ON DELETE RESTRICT"
+    );
+    assert_eq!(
+        lines[3],
+        "main\tpayment\tfk_payment_order\
+        \t**(order_id) REFERENCES order(id) ON UPDATE RESTRICT ON DELETE CASCADE\t\t"
+    );
+    Ok(())
+}
+
+#[test]
+fn test_foreign_key_other_schema() -> Result<()> {
+    let configs = read_config("tests/resources/datasource.json")?;
+    let deploys = build_deploy(&configs)?;
+    let foreign_keys_json = &read_to_string("tests/resources/snapshot/foreign-keys.json")?;
+    let mut foreign_keys: Vec<ForeignKeyInfo> = serde_json::from_str(foreign_keys_json)?;
+    foreign_keys[2].referenced_schema = "archive".to_string();
+    let output_file = "target/test-output/diff-foreign-keys-other-schema.csv";
+    output_foreign_key_info(output_file, &foreign_keys, &[], &deploys, b'\t', false)?;
+
+    let output = read_to_string(output_file)?;
+    let lines: Vec<&str> = output.lines().collect();
+    assert_eq!(
+        lines[1],
+        "main\torder_item\tfk_order_item_order\
+        \t(order_id) REFERENCES order(id) ON UPDATE RESTRICT ON DELETE CASCADE\
+        \t(order_id) REFERENCES order(id) ON UPDATE RESTRICT ON DELETE CASCADE\
+        \t**(order_id) REFERENCES archive.order(id) ON UPDATE RESTRICT ON DELETE CASCADE"
+    );
+    Ok(())
+}
+
+#[test]
+fn test_only_diff() -> Result<()> {
+    INIT.call_once(|| {
+        init_log("Info").unwrap();
+    });
+    let configs = read_config("tests/resources/datasource.json")?;
     let deploys = build_deploy(&configs)?;
-    let indices_json = &read_to_string("indices.json")?;
-    let mut indices = serde_json::from_str(indices_json)?;
-    output_index_info("test-diff-indices.csv", &mut indices, &deploys)?;
+    let foreign_keys_json = &read_to_string("tests/resources/snapshot/foreign-keys.json")?;
+    let foreign_keys: Vec<ForeignKeyInfo> = serde_json::from_str(foreign_keys_json)?;
+    let output_file = "target/test-output/diff-foreign-keys-only-diff.csv";
+    output_foreign_key_info(output_file, &foreign_keys, &[], &deploys, b',', true)?;
+
+    let output = read_to_string(output_file)?;
+    let lines: Vec<&str> = output.lines().collect();
+    assert_eq!(lines.len(), 4);
+    assert!(lines[1].starts_with("main,order_item,fk_order_item_product,"));
+    assert!(lines[2].starts_with("main,payment,fk_payment_order,"));
+    assert_eq!(lines[3], "1 identical objects omitted,,,,,");
     Ok(())
 }
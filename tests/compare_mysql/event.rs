@@ -0,0 +1,41 @@
+use std::fs::read_to_string;
+
+use scomp::compare_mysql::{compare, load_snapshot};
+use scomp::value_object::{DriftCategory, OutputOptions, Result};
+
+#[test]
+fn test_output_events() -> Result<()> {
+    let mut snapshot = load_snapshot("tests/resources/snapshot")?;
+    let options = OutputOptions {
+        events: Some("target/test-output/event/diff-events.csv".to_string()),
+        check: true,
+        ..Default::default()
+    };
+    // ug was created later, its start time isn't compared
+    let drift = compare(&snapshot, &options)?.unwrap();
+    assert_eq!(drift.counts.get(&DriftCategory::Event), Some(&1));
+
+    let events = read_to_string(options.events.as_ref().unwrap())?;
+    assert!(events.starts_with("Schema,Event,[uat ke],[uat tz],[uat ug]\n"));
+    assert!(events.contains(
+        "main,purge_sessions,\
+         \"EVERY 1 DAY TIME ZONE SYSTEM ON COMPLETION NOT PRESERVE ENABLED\n\
+         delete from session where expired_at < now()\",\
+         \"**EVERY 1 DAY TIME ZONE SYSTEM ON COMPLETION NOT PRESERVE DISABLED\n\
+         delete from session where expired_at < now()\",\
+         \"EVERY 1 DAY TIME ZONE SYSTEM ON COMPLETION NOT PRESERVE ENABLED\n"
+    ));
+    assert!(!events.contains("STARTS"));
+
+    // a different interval in one deploy, missing in another
+    snapshot.events.retain(|e| e.country != "ug");
+    snapshot.events[1].schedule = "EVERY 1 HOUR STARTS 2026-01-01 03:00:00".to_string();
+    snapshot.events[1].status = "ENABLED".to_string();
+    let drift = compare(&snapshot, &options)?.unwrap();
+    assert_eq!(drift.counts.get(&DriftCategory::Event), Some(&1));
+    assert_eq!(drift.counts.get(&DriftCategory::MissingEvent), Some(&1));
+    let events = read_to_string(options.events.as_ref().unwrap())?;
+    assert!(events.contains("\"**EVERY 1 HOUR TIME ZONE SYSTEM"));
+    assert!(events.ends_with(",**\n"));
+    Ok(())
+}
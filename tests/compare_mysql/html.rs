@@ -0,0 +1,73 @@
+use std::fs::read_to_string;
+
+use scomp::compare_mysql::{build_deploy, load_snapshot, output_html};
+use scomp::value_object::{DiffCell, DiffRow, ObjectKind, Result};
+
+#[test]
+fn test_output_html() -> Result<()> {
+    let snapshot = load_snapshot("tests/resources/snapshot")?;
+    let deploys = build_deploy(&snapshot.sources)?;
+    let cell = |value: &str, reference: &str| DiffCell::new(value.to_string(), reference);
+    let rows = vec![
+        DiffRow {
+            kind: ObjectKind::Column,
+            schema: "main".to_string(),
+            table: "customer".to_string(),
+            name: "email".to_string(),
+            routine_type: String::new(),
+            reference: "varchar(128) NULL".to_string(),
+            cells: vec![
+                cell("varchar(64) NULL", "varchar(128) NULL"),
+                cell("varchar(128) NULL", "varchar(128) NULL"),
+                cell("", "varchar(128) NULL"),
+            ],
+        },
+        DiffRow {
+            kind: ObjectKind::Column,
+            schema: "main".to_string(),
+            table: "payment".to_string(),
+            name: "note".to_string(),
+            routine_type: String::new(),
+            reference: "varchar(16) COMMENT '<none>'".to_string(),
+            cells: vec![
+                cell(
+                    "varchar(16) COMMENT '<none>'",
+                    "varchar(16) COMMENT '<none>'",
+                ),
+                cell(
+                    "varchar(16) COMMENT '<none>'",
+                    "varchar(16) COMMENT '<none>'",
+                ),
+                DiffCell::unreachable(),
+            ],
+        },
+    ];
+
+    let file = "target/test-output/report.html";
+    output_html(file, &deploys, &rows, false)?;
+
+    let html = read_to_string(file)?;
+    // no external assets
+    assert!(!html.contains("src="));
+    assert!(!html.contains("<link"));
+    assert!(html.contains("<td class=\"value outlier\" data-environment=\"uat\" data-country=\"ke\">varchar(64) NULL</td>"));
+    assert!(html.contains(
+        "<td class=\"value missing\" data-environment=\"uat\" data-country=\"ug\">MISSING</td>"
+    ));
+    assert!(html.contains("<td class=\"value unreachable\" data-environment=\"uat\" data-country=\"ug\">UNREACHABLE</td>"));
+    assert!(html.contains("varchar(16) COMMENT &#39;&lt;none&gt;&#39;"));
+    assert!(html.contains("<summary>1 tables identical in all deploys</summary>"));
+    assert!(html.contains("<tr><td>Column</td><td>2</td><td>1</td><td>1</td></tr>"));
+
+    // identical tables are collapsed
+    let details = html.find("<details>").unwrap();
+    assert!(html.find("<h3>main.customer</h3>").unwrap() < details);
+    assert!(html.find("<h3>main.payment</h3>").unwrap() > details);
+
+    output_html(file, &deploys, &rows, true)?;
+    let html = read_to_string(file)?;
+    assert!(html.contains("<h3>main.customer</h3>"));
+    assert!(!html.contains("<h3>main.payment</h3>"));
+    assert!(html.contains("<p>1 tables identical in all deploys omitted.</p>"));
+    Ok(())
+}
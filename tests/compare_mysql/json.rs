@@ -0,0 +1,54 @@
+use serde_json::Value;
+use std::fs::read_to_string;
+
+use scomp::compare_mysql::{compare, load_snapshot};
+use scomp::value_object::{OutputOptions, Result};
+
+#[test]
+fn test_output_json() -> Result<()> {
+    let snapshot = load_snapshot("tests/resources/snapshot")?;
+    let options = OutputOptions {
+        columns: Some("target/test-output/json/diff-columns.csv".to_string()),
+        indices: Some("target/test-output/json/diff-indices.csv".to_string()),
+        foreign_keys: Some("target/test-output/json/diff-foreign-keys.csv".to_string()),
+        json: Some("target/test-output/json/diff.json".to_string()),
+        json_lines: Some("target/test-output/json/diff.jsonl".to_string()),
+        ..Default::default()
+    };
+    compare(&snapshot, &options)?;
+
+    let document: Value = serde_json::from_str(&read_to_string(options.json.as_ref().unwrap())?)?;
+    assert_eq!(document["deploys"].as_array().unwrap().len(), 3);
+    let differences = document["differences"].as_array().unwrap();
+    let email = differences
+        .iter()
+        .find(|d| d["kind"] == "column" && d["key"]["column"] == "email")
+        .unwrap();
+    assert_eq!(email["key"]["table"], "customer");
+    assert_eq!(email["reference"], "varchar(128) NULL");
+    assert_eq!(email["deploys"][0]["country"], "ke");
+    assert_eq!(email["deploys"][0]["value"], "varchar(64) NULL");
+    assert_eq!(email["deploys"][0]["differs"], true);
+    assert_eq!(email["deploys"][1]["differs"], false);
+
+    // absent objects are null
+    let legacy_code = differences
+        .iter()
+        .find(|d| d["key"]["column"] == "legacy_code")
+        .unwrap();
+    assert_eq!(legacy_code["reference"], Value::Null);
+    assert_eq!(legacy_code["deploys"][0]["value"], Value::Null);
+
+    // identical objects are left out
+    assert!(!differences
+        .iter()
+        .any(|d| d["key"]["table"] == "customer" && d["key"]["column"] == "id"));
+
+    let lines = read_to_string(options.json_lines.as_ref().unwrap())?;
+    assert_eq!(lines.lines().count(), differences.len());
+    for (line, difference) in lines.lines().zip(differences) {
+        let value: Value = serde_json::from_str(line)?;
+        assert_eq!(&value, difference);
+    }
+    Ok(())
+}
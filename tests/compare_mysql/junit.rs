@@ -0,0 +1,34 @@
+use std::fs::read_to_string;
+
+use scomp::compare_mysql::{compare, load_snapshot};
+use scomp::value_object::{OutputOptions, Result};
+
+#[test]
+fn test_output_junit() -> Result<()> {
+    let snapshot = load_snapshot("tests/resources/snapshot")?;
+    let options = OutputOptions {
+        columns: Some("target/test-output/junit/diff-columns.csv".to_string()),
+        indices: Some("target/test-output/junit/diff-indices.csv".to_string()),
+        foreign_keys: Some("target/test-output/junit/diff-foreign-keys.csv".to_string()),
+        junit: Some("target/test-output/junit/report.xml".to_string()),
+        ..Default::default()
+    };
+    compare(&snapshot, &options)?;
+
+    let report = read_to_string(options.junit.as_ref().unwrap())?;
+    assert!(report.starts_with("<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n"));
+    assert!(report.contains("<testsuites name=\"scomp\" tests=\"29\" failures=\"19\">"));
+    assert!(report.contains("<testsuite name=\"main.customer\" tests=\"8\" failures=\"4\">"));
+    assert!(report.contains("<testcase classname=\"main.customer\" name=\"Column id\"/>"));
+    assert!(report.contains(
+        "<testcase classname=\"main.customer\" name=\"Column email\">\n      \
+         <failure type=\"drift\" message=\"1 deploys differ from the most used definition\">\
+         Reference: varchar(128) NULL\n[uat ke] varchar(64) NULL</failure>"
+    ));
+    assert!(report.contains("Reference: none\n[uat ug] varchar(16) NULL</failure>"));
+    assert_eq!(
+        report.matches("<testsuite ").count(),
+        report.matches("</testsuite>").count()
+    );
+    Ok(())
+}
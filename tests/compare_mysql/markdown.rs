@@ -0,0 +1,96 @@
+use std::fs::read_to_string;
+
+use scomp::compare_mysql::{build_deploy, compare, load_snapshot, output_markdown, read_config};
+use scomp::value_object::{DiffCell, DiffRow, ObjectKind, OutputOptions, Result};
+
+fn options(name: &str, max_size: Option<usize>) -> OutputOptions {
+    OutputOptions {
+        columns: Some(format!("target/test-output/markdown/{}-columns.csv", name)),
+        indices: Some(format!("target/test-output/markdown/{}-indices.csv", name)),
+        foreign_keys: Some(format!(
+            "target/test-output/markdown/{}-foreign-keys.csv",
+            name
+        )),
+        markdown: Some(format!("target/test-output/markdown/{}.md", name)),
+        markdown_max_size: max_size,
+        ..Default::default()
+    }
+}
+
+#[test]
+fn test_output_markdown() -> Result<()> {
+    let snapshot = load_snapshot("tests/resources/snapshot")?;
+    let options = options("report", None);
+    compare(&snapshot, &options)?;
+
+    let report = read_to_string(options.markdown.as_ref().unwrap())?;
+    assert!(report.starts_with("# Schema comparison\n\n6 of 6 tables differ"));
+    assert!(report.contains(
+        "## main.customer\n\n**Column `email`**\n\n\
+         | Deploys | Definition |\n\
+         | --- | --- |\n\
+         | uat tz, uat ug | `varchar(128) NULL` (reference) |\n\
+         | uat ke | `varchar(64) NULL` |\n"
+    ));
+    assert!(
+        report.contains("| uat ke, uat tz | _none_ (reference) |\n| uat ug | `varchar(16) NULL` |")
+    );
+    // identical objects are left out
+    assert!(!report.contains("**Column `name`**"));
+    assert!(!report.contains("left out"));
+    Ok(())
+}
+
+#[test]
+fn test_output_markdown_max_size() -> Result<()> {
+    let snapshot = load_snapshot("tests/resources/snapshot")?;
+    let options = options("capped", Some(800));
+    compare(&snapshot, &options)?;
+
+    let report = read_to_string(options.markdown.as_ref().unwrap())?;
+    assert!(report.len() <= 800);
+    assert!(report.contains("## main\n\n**View `active_sessions`**"));
+    assert!(!report.contains("## main.customer"));
+    assert!(
+        report.contains("more differing objects are left out to keep the report under 800 bytes._")
+    );
+    Ok(())
+}
+
+#[test]
+fn test_output_markdown_min_size() -> Result<()> {
+    let snapshot = load_snapshot("tests/resources/snapshot")?;
+    let options = options("too-small", Some(100));
+    let error = compare(&snapshot, &options).unwrap_err();
+    assert!(error
+        .to_string()
+        .starts_with("Markdown report can't be capped at 100 bytes"));
+    Ok(())
+}
+
+#[test]
+fn test_output_markdown_backticks() -> Result<()> {
+    let configs = read_config("tests/resources/datasource.json")?;
+    let deploys = build_deploy(&configs)?;
+    let reference = "select `id` from `customer`".to_string();
+    let rows = vec![DiffRow {
+        kind: ObjectKind::View,
+        schema: "main".to_string(),
+        table: String::new(),
+        name: "active_customer".to_string(),
+        routine_type: String::new(),
+        cells: vec![
+            DiffCell::new(reference.clone(), &reference),
+            DiffCell::new(reference.clone(), &reference),
+            DiffCell::new("`id` | 1".to_string(), &reference),
+        ],
+        reference,
+    }];
+    let file = "target/test-output/markdown/backticks.md";
+    output_markdown(file, &deploys, &rows, None)?;
+
+    let report = read_to_string(file)?;
+    assert!(report.contains("| uat ke, uat tz | `` select `id` from `customer` `` (reference) |\n"));
+    assert!(report.contains("| uat ug | `` `id` \\| 1 `` |\n"));
+    Ok(())
+}
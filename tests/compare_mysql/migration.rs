@@ -0,0 +1,226 @@
+use std::fs::read_to_string;
+
+use scomp::compare_mysql::{build_deploy, build_migration, output_migration, read_config};
+use scomp::value_object::{ColumnInfo, ColumnRules, Engine, ForeignKeyInfo, IndexInfo, Result};
+
+type Source = (Vec<ColumnInfo>, Vec<IndexInfo>, Vec<ForeignKeyInfo>);
+
+fn load_source() -> Result<Source> {
+    let columns = serde_json::from_str(&read_to_string("tests/resources/snapshot/columns.json")?)?;
+    let indices = serde_json::from_str(&read_to_string("tests/resources/snapshot/indices.json")?)?;
+    let foreign_keys = serde_json::from_str(&read_to_string(
+        "tests/resources/snapshot/foreign-keys.json",
+    )?)?;
+    Ok((columns, indices, foreign_keys))
+}
+
+#[test]
+fn test_build_migration() -> Result<()> {
+    let configs = read_config("tests/resources/datasource.json")?;
+    let deploys = build_deploy(&configs)?;
+    let (columns, indices, foreign_keys) = load_source()?;
+    let rules = ColumnRules::default();
+
+    let ke = build_migration(
+        &columns,
+        &indices,
+        &foreign_keys,
+        &deploys,
+        &deploys[0],
+        &rules,
+    );
+    assert_eq!(
+        ke,
+        vec![
+            "ALTER TABLE `payment` DROP FOREIGN KEY `fk_payment_order`;",
+            "ALTER TABLE `customer` MODIFY COLUMN `email` varchar(128) NULL;",
+        ]
+    );
+
+    let tz = build_migration(
+        &columns,
+        &indices,
+        &foreign_keys,
+        &deploys,
+        &deploys[1],
+        &rules,
+    );
+    assert_eq!(
+        tz,
+        vec![
+            "CREATE TABLE `order` (\n  \
+            `id` bigint NOT NULL,\n  \
+            `customer_id` bigint NOT NULL,\n  \
+            `currency` char(3) NOT NULL DEFAULT 'KES',\n  \
+            `created_at` datetime NOT NULL DEFAULT CURRENT_TIMESTAMP\n);",
+            "ALTER TABLE `order` ADD PRIMARY KEY (`id`);",
+            "CREATE INDEX `idx_order_customer` ON `order` (`customer_id`, `created_at`);",
+        ]
+    );
+
+    // the foreign key is dropped first and added back last, the index before its column
+    let ug = build_migration(
+        &columns,
+        &indices,
+        &foreign_keys,
+        &deploys,
+        &deploys[2],
+        &rules,
+    );
+    assert_eq!(
+        ug,
+        vec![
+            "ALTER TABLE `order_item` DROP FOREIGN KEY `fk_order_item_product`;",
+            "DROP INDEX `idx_customer_legacy` ON `customer`;",
+            "ALTER TABLE `customer` DROP COLUMN `legacy_code`;",
+            "CREATE UNIQUE INDEX `idx_customer_email` ON `customer` (`email`);",
+            "ALTER TABLE `order_item` ADD CONSTRAINT `fk_order_item_product` FOREIGN KEY (`product_id`) \
+             REFERENCES `product` (`id`) ON UPDATE RESTRICT ON DELETE CASCADE;",
+        ]
+    );
+    Ok(())
+}
+
+#[test]
+fn test_output_migration() -> Result<()> {
+    let configs = read_config("tests/resources/datasource.json")?;
+    let deploys = build_deploy(&configs)?;
+    let (columns, indices, foreign_keys) = load_source()?;
+
+    let dir = "target/test-output/migration";
+    output_migration(
+        dir,
+        &columns,
+        &indices,
+        &foreign_keys,
+        &deploys,
+        &ColumnRules::default(),
+    )?;
+
+    let output = read_to_string(format!("{}/uat-ke.sql", dir))?;
+    assert_eq!(
+        output,
+        "-- Migrate [uat ke] to the most used definition\n\
+        ALTER TABLE `payment` DROP FOREIGN KEY `fk_payment_order`;\n\
+        ALTER TABLE `customer` MODIFY COLUMN `email` varchar(128) NULL;\n"
+    );
+    Ok(())
+}
+
+#[test]
+fn test_migration_foreign_key_index() -> Result<()> {
+    let configs = read_config("tests/resources/datasource.json")?;
+    let deploys = build_deploy(&configs)?;
+    let (columns, mut indices, mut foreign_keys) = load_source()?;
+    // orders reference their customer in ke and ug, ug indexes only the customer
+    for country in ["ke", "ug"] {
+        let mut foreign_key = foreign_keys[0].clone();
+        foreign_key.country = country.to_string();
+        foreign_key.table = "order".to_string();
+        foreign_key.constraint_name = "fk_order_customer".to_string();
+        foreign_key.column = "customer_id".to_string();
+        foreign_key.referenced_table = "customer".to_string();
+        foreign_key.referenced_column = "id".to_string();
+        foreign_keys.push(foreign_key);
+    }
+    indices.retain(|i| {
+        !(i.country == "ug" && i.index_name == "idx_order_customer" && i.sequence == 2)
+    });
+    let rules = ColumnRules::default();
+
+    // the unchanged foreign key is dropped before the index backing it, and added back after it
+    let ug = build_migration(
+        &columns,
+        &indices,
+        &foreign_keys,
+        &deploys,
+        &deploys[2],
+        &rules,
+    );
+    assert_eq!(
+        ug,
+        vec![
+            "ALTER TABLE `order` DROP FOREIGN KEY `fk_order_customer`;",
+            "ALTER TABLE `order_item` DROP FOREIGN KEY `fk_order_item_product`;",
+            "DROP INDEX `idx_customer_legacy` ON `customer`;",
+            "DROP INDEX `idx_order_customer` ON `order`;",
+            "ALTER TABLE `customer` DROP COLUMN `legacy_code`;",
+            "CREATE UNIQUE INDEX `idx_customer_email` ON `customer` (`email`);",
+            "CREATE INDEX `idx_order_customer` ON `order` (`customer_id`, `created_at`);",
+            "ALTER TABLE `order` ADD CONSTRAINT `fk_order_customer` FOREIGN KEY (`customer_id`) \
+             REFERENCES `customer` (`id`) ON UPDATE RESTRICT ON DELETE CASCADE;",
+            "ALTER TABLE `order_item` ADD CONSTRAINT `fk_order_item_product` FOREIGN KEY (`product_id`) \
+             REFERENCES `product` (`id`) ON UPDATE RESTRICT ON DELETE CASCADE;",
+        ]
+    );
+    Ok(())
+}
+
+#[test]
+fn test_migration_other_engine() -> Result<()> {
+    let mut configs = read_config("tests/resources/datasource.json")?;
+    configs[1].engine = Engine::Postgres;
+    let deploys = build_deploy(&configs)?;
+    let (columns, indices, foreign_keys) = load_source()?;
+
+    let error = output_migration(
+        "target/test-output/migration-postgres",
+        &columns,
+        &indices,
+        &foreign_keys,
+        &deploys,
+        &ColumnRules::default(),
+    )
+    .unwrap_err();
+    assert_eq!(
+        error.to_string(),
+        "Migration scripts are only written for MySQL, [uat tz] is a postgres deploy"
+    );
+    Ok(())
+}
+
+#[test]
+fn test_migration_extra() -> Result<()> {
+    let configs = read_config("tests/resources/datasource.json")?;
+    let deploys = build_deploy(&configs)?;
+    let (mut columns, indices, foreign_keys) = load_source()?;
+    for column in columns.iter_mut() {
+        match column.column_name.as_str() {
+            "email" => column.extra = Some("VIRTUAL GENERATED".to_string()),
+            "created_at" => {
+                column.extra = Some("DEFAULT_GENERATED on update CURRENT_TIMESTAMP".to_string())
+            }
+            _ => {}
+        }
+    }
+    let rules = ColumnRules::default();
+
+    let ke = build_migration(
+        &columns,
+        &indices,
+        &foreign_keys,
+        &deploys,
+        &deploys[0],
+        &rules,
+    );
+    assert_eq!(
+        ke,
+        vec![
+            "ALTER TABLE `payment` DROP FOREIGN KEY `fk_payment_order`;",
+            "-- manual: modify generated column `customer`.`email` (varchar(128)) with its expression",
+        ]
+    );
+
+    let tz = build_migration(
+        &columns,
+        &indices,
+        &foreign_keys,
+        &deploys,
+        &deploys[1],
+        &rules,
+    );
+    assert!(tz[0].ends_with(
+        "`created_at` datetime NOT NULL DEFAULT CURRENT_TIMESTAMP ON UPDATE CURRENT_TIMESTAMP\n);"
+    ));
+    Ok(())
+}
@@ -1,2 +1,32 @@
 #[cfg(test)]
+mod check;
+#[cfg(test)]
 mod collector;
+#[cfg(test)]
+mod event;
+#[cfg(test)]
+mod html;
+#[cfg(test)]
+mod json;
+#[cfg(test)]
+mod junit;
+#[cfg(test)]
+mod markdown;
+#[cfg(test)]
+mod migration;
+#[cfg(test)]
+mod partition;
+#[cfg(test)]
+mod postgres;
+#[cfg(test)]
+mod report;
+#[cfg(test)]
+mod routine;
+#[cfg(test)]
+mod snapshot;
+#[cfg(test)]
+mod sqlite;
+#[cfg(test)]
+mod view;
+#[cfg(test)]
+mod xlsx;
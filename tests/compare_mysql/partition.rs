@@ -0,0 +1,76 @@
+use std::fs::read_to_string;
+
+use scomp::compare_mysql::{compare, load_snapshot};
+use scomp::value_object::{DriftCategory, OutputOptions, Result};
+
+#[test]
+fn test_output_partitions() -> Result<()> {
+    let snapshot = load_snapshot("tests/resources/snapshot")?;
+    let options = OutputOptions {
+        partitions: Some("target/test-output/partition/diff-partitions.csv".to_string()),
+        check: true,
+        ..Default::default()
+    };
+    let drift = compare(&snapshot, &options)?.unwrap();
+    assert_eq!(drift.counts.get(&DriftCategory::Partition), Some(&2));
+
+    // tz has rolled forward a year, ug isn't partitioned
+    let partitions = read_to_string(options.partitions.as_ref().unwrap())?;
+    assert!(partitions.starts_with("Schema,Table,[uat ke],[uat tz],[uat ug]\n"));
+    assert!(partitions.contains(
+        "main,audit_log,\"RANGE(year(created_at))\n\
+         p2025 VALUES LESS THAN (2026), p2026 VALUES LESS THAN (2027)\",\
+         \"**RANGE(year(created_at))\n\
+         p2026 VALUES LESS THAN (2027), p2027 VALUES LESS THAN (2028)\",\
+         **NOT PARTITIONED\n"
+    ));
+    // ug has twice the hash partitions
+    assert!(partitions.contains(
+        "main,session,\"HASH(id)\np0, p1, p2, p3\",\"HASH(id)\np0, p1, p2, p3\",\
+         \"**HASH(id)\np0, p1, p2, p3, p4, p5, p6, p7\"\n"
+    ));
+    Ok(())
+}
+
+#[test]
+fn test_ignore_partition_boundaries() -> Result<()> {
+    let snapshot = load_snapshot("tests/resources/snapshot")?;
+    let options = OutputOptions {
+        partitions: Some("target/test-output/partition/ignore-boundaries.csv".to_string()),
+        ignore_partition_boundaries: true,
+        ..Default::default()
+    };
+    compare(&snapshot, &options)?;
+
+    // range bounds are left out, the number of hash partitions still differs
+    let partitions = read_to_string(options.partitions.as_ref().unwrap())?;
+    assert!(partitions.contains(
+        "main,audit_log,RANGE(year(created_at)),RANGE(year(created_at)),**NOT PARTITIONED\n"
+    ));
+    assert!(partitions.contains("\"**HASH(id)\np0, p1, p2, p3, p4, p5, p6, p7\"\n"));
+    Ok(())
+}
+
+#[test]
+fn test_partitions_of_missing_table() -> Result<()> {
+    let mut snapshot = load_snapshot("tests/resources/snapshot")?;
+    snapshot
+        .tables
+        .retain(|t| !(t.table == "session" && t.country == "ug"));
+    snapshot
+        .partitions
+        .retain(|p| !(p.table == "session" && p.country == "ug"));
+    let options = OutputOptions {
+        partitions: Some("target/test-output/partition/missing-table.csv".to_string()),
+        check: true,
+        ..Default::default()
+    };
+    let drift = compare(&snapshot, &options)?.unwrap();
+
+    // ug's session is only reported as a missing table
+    let partitions = read_to_string(options.partitions.as_ref().unwrap())?;
+    assert!(!partitions.contains("main,session,"));
+    assert_eq!(drift.counts.get(&DriftCategory::Partition), Some(&1));
+    assert_eq!(drift.counts.get(&DriftCategory::MissingTable), Some(&1));
+    Ok(())
+}
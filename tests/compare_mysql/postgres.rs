@@ -0,0 +1,190 @@
+use postgres::NoTls;
+
+use scomp::compare_mysql::{collect, read_config};
+use scomp::value_object::{CollectOptions, ConnectInfo, Engine, Result};
+
+fn prepare_schema(config: &ConnectInfo) -> Result<()> {
+    let mut client = postgres::Config::new()
+        .host(&config.host)
+        .port(config.port as u16)
+        .user(&config.user)
+        .password(&config.password)
+        .dbname(config.database.as_ref().unwrap())
+        .connect(NoTls)?;
+
+    client.batch_execute(
+        "drop schema if exists scomp_test cascade;
+        drop role if exists scomp_reader;
+        create schema scomp_test;
+        create table scomp_test.customer (
+            id bigint primary key,
+            name varchar(64) not null,
+            email varchar(128)
+        );
+        comment on table scomp_test.customer is 'customers';
+        comment on column scomp_test.customer.name is 'display name';
+        create unique index idx_customer_email on scomp_test.customer (email);
+        create table scomp_test.orders (
+            id bigint generated always as identity primary key,
+            customer_id bigint not null references scomp_test.customer (id) on delete cascade,
+            status smallint not null default 1
+        );
+        create index idx_orders_customer on scomp_test.orders (customer_id, status);
+        create function scomp_test.order_count(customer bigint, open_only boolean default true)
+        returns bigint language sql as
+        $$ select count(*) from scomp_test.orders where customer_id = customer $$;
+        create procedure scomp_test.close_orders(customer bigint) language sql as
+        $$ update scomp_test.orders set status = 0 where customer_id = customer $$;
+        create function scomp_test.touch_customer() returns trigger language plpgsql as
+        $$ begin return new; end $$;
+        create trigger trg_customer_touch before insert or update on scomp_test.customer
+        for each row execute function scomp_test.touch_customer();
+        create view scomp_test.open_orders as
+        select id, customer_id from scomp_test.orders where status = 1
+        with local check option;
+        create table scomp_test.audit_log (
+            id bigint not null,
+            created_at date not null
+        ) partition by range (created_at);
+        create table scomp_test.audit_log_2025 partition of scomp_test.audit_log
+        for values from ('2025-01-01') to ('2026-01-01');
+        create table scomp_test.audit_log_2026 partition of scomp_test.audit_log
+        for values from ('2026-01-01') to ('2027-01-01');
+        create role scomp_reader login password 'scomp_reader';
+        grant usage on schema scomp_test to scomp_reader;
+        grant select on all tables in schema scomp_test to scomp_reader;
+        revoke select on scomp_test.open_orders from scomp_reader;
+        grant trigger on scomp_test.open_orders to scomp_reader;",
+    )?;
+    Ok(())
+}
+
+#[test]
+#[ignore = "requires a local PostgreSQL instance, see tests/resources/datasource-postgres.json"]
+fn test_collect_postgres() -> Result<()> {
+    let configs = read_config("tests/resources/datasource-postgres.json")?;
+    assert_eq!(configs[0].engine, Engine::Postgres);
+    prepare_schema(&configs[0])?;
+
+    let snapshot = collect(&configs, &CollectOptions::default())?;
+
+    // partitions are only compared as part of their table
+    assert!(snapshot.tables.iter().any(|t| t.table == "audit_log"));
+    assert!(!snapshot.tables.iter().any(|t| t.table == "audit_log_2025"));
+    assert!(!snapshot.columns.iter().any(|c| c.table == "audit_log_2025"));
+
+    let customer = snapshot
+        .tables
+        .iter()
+        .find(|t| t.table == "customer")
+        .unwrap();
+    assert_eq!(customer.engine.as_deref(), Some("heap"));
+    assert_eq!(customer.comment.as_deref(), Some("customers"));
+    assert!(!customer.auto_increment);
+    assert!(snapshot
+        .tables
+        .iter()
+        .any(|t| t.table == "orders" && t.auto_increment));
+
+    let name = snapshot
+        .columns
+        .iter()
+        .find(|c| c.table == "customer" && c.column_name == "name")
+        .unwrap();
+    assert_eq!(name.column_type, "character varying(64)");
+    assert_eq!(name.nullable, "NO");
+    assert_eq!(name.comment.as_deref(), Some("display name"));
+
+    let status = snapshot
+        .columns
+        .iter()
+        .find(|c| c.table == "orders" && c.column_name == "status")
+        .unwrap();
+    assert_eq!(status.default_value.as_deref(), Some("1"));
+
+    let mut index: Vec<(i32, &str)> = snapshot
+        .indices
+        .iter()
+        .filter(|i| i.index_name == "idx_orders_customer")
+        .map(|i| (i.sequence, i.column.as_str()))
+        .collect();
+    index.sort();
+    assert_eq!(index, vec![(1, "customer_id"), (2, "status")]);
+    assert!(snapshot
+        .indices
+        .iter()
+        .any(|i| i.index_name == "idx_customer_email" && i.unique));
+
+    assert_eq!(snapshot.foreign_keys.len(), 1);
+    let foreign_key = &snapshot.foreign_keys[0];
+    assert_eq!(foreign_key.table, "orders");
+    assert_eq!(foreign_key.column, "customer_id");
+    assert_eq!(foreign_key.referenced_schema, "scomp_test");
+    assert_eq!(foreign_key.referenced_table, "customer");
+    assert_eq!(foreign_key.referenced_column, "id");
+    assert_eq!(foreign_key.delete_rule, "CASCADE");
+
+    let function = snapshot
+        .routines
+        .iter()
+        .find(|r| r.routine_type == "FUNCTION")
+        .unwrap();
+    assert_eq!(
+        function.routine_name,
+        "order_count(customer bigint, open_only boolean)"
+    );
+    assert_eq!(
+        function.parameters,
+        "customer bigint, open_only boolean DEFAULT true"
+    );
+    assert_eq!(function.returns.as_deref(), Some("bigint"));
+    assert!(function.definition.contains("select count(*)"));
+    let procedure = snapshot
+        .routines
+        .iter()
+        .find(|r| r.routine_type == "PROCEDURE")
+        .unwrap();
+    assert_eq!(procedure.routine_name, "close_orders(IN customer bigint)");
+    assert_eq!(procedure.returns, None);
+
+    assert_eq!(snapshot.partitions.len(), 2);
+    let partition = &snapshot.partitions[0];
+    assert_eq!(partition.table, "audit_log");
+    assert_eq!(partition.partition_name, "audit_log_2025");
+    assert_eq!(partition.ordinal_position, 1);
+    assert_eq!(partition.method, "RANGE");
+    assert_eq!(partition.expression.as_deref(), Some("created_at"));
+    assert_eq!(
+        partition.bound.as_deref(),
+        Some("FOR VALUES FROM ('2025-01-01') TO ('2026-01-01')")
+    );
+
+    assert_eq!(snapshot.views.len(), 1);
+    let view = &snapshot.views[0];
+    assert_eq!(view.view_name, "open_orders");
+    assert_eq!(view.columns, "id, customer_id");
+    assert_eq!(view.check_option, "LOCAL");
+    assert!(view.updatable);
+    assert!(view.definition.contains("WHERE (orders.status = 1)"));
+
+    // a read only role which doesn't own the view
+    let mut reader = configs[0].clone();
+    reader.user = "scomp_reader".to_string();
+    reader.password = "scomp_reader".to_string();
+    let read = collect(&[reader], &CollectOptions::default())?;
+    assert_eq!(read.views.len(), 1);
+    assert_eq!(read.views[0].columns, view.columns);
+    assert_eq!(read.views[0].definition, view.definition);
+
+    assert_eq!(snapshot.triggers.len(), 1);
+    let trigger = &snapshot.triggers[0];
+    assert_eq!(trigger.table, "customer");
+    assert_eq!(trigger.trigger_name, "trg_customer_touch");
+    assert_eq!(trigger.timing, "BEFORE");
+    assert_eq!(trigger.event, "INSERT OR UPDATE");
+    assert_eq!(
+        trigger.statement,
+        "EXECUTE FUNCTION scomp_test.touch_customer()"
+    );
+    Ok(())
+}
@@ -0,0 +1,58 @@
+use std::fs::read_to_string;
+
+use scomp::compare_mysql::{compare, load_diff, load_snapshot, report, DIFF_VERSION};
+use scomp::value_object::{OutputOptions, Result};
+
+#[test]
+fn test_report() -> Result<()> {
+    let snapshot = load_snapshot("tests/resources/snapshot")?;
+    let options = OutputOptions {
+        diff: Some("target/test-output/report/diff.json".to_string()),
+        markdown: Some("target/test-output/report/compare.md".to_string()),
+        columns: Some("target/test-output/report/diff-columns.csv".to_string()),
+        ..Default::default()
+    };
+    compare(&snapshot, &options)?;
+
+    let diff = load_diff(options.diff.as_ref().unwrap())?;
+    assert_eq!(diff.version, DIFF_VERSION);
+    assert_eq!(diff.deploys.len(), 3);
+    assert_eq!(diff.rows.len(), 29);
+
+    // reports rendered from a saved diff are the same as from the comparison
+    let rendered = OutputOptions {
+        markdown: Some("target/test-output/report/report.md".to_string()),
+        ..Default::default()
+    };
+    report(&diff, &rendered)?;
+    assert_eq!(
+        read_to_string(rendered.markdown.as_ref().unwrap())?,
+        read_to_string(options.markdown.as_ref().unwrap())?
+    );
+
+    let rendered = OutputOptions {
+        csv: Some("target/test-output/report/csv".to_string()),
+        ..Default::default()
+    };
+    report(&diff, &rendered)?;
+    assert_eq!(
+        read_to_string("target/test-output/report/csv/diff-columns.csv")?,
+        read_to_string(options.columns.as_ref().unwrap())?
+    );
+    Ok(())
+}
+
+#[test]
+fn test_load_diff_version() -> Result<()> {
+    let file = "target/test-output/report/future.json";
+    std::fs::create_dir_all("target/test-output/report")?;
+    std::fs::write(
+        file,
+        format!(
+            "{{\"version\":{},\"created_at\":\"\",\"deploys\":[],\"rows\":[]}}",
+            DIFF_VERSION + 1
+        ),
+    )?;
+    assert!(load_diff(file).is_err());
+    Ok(())
+}
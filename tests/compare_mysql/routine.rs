@@ -0,0 +1,121 @@
+use std::fs::read_to_string;
+
+use scomp::compare_mysql::{compare, load_snapshot, normalize_definition};
+use scomp::value_object::{DriftCategory, Engine, OutputOptions, Result};
+
+#[test]
+fn test_output_routines() -> Result<()> {
+    let snapshot = load_snapshot("tests/resources/snapshot")?;
+    let options = OutputOptions {
+        routines: Some("target/test-output/routine/diff-routines.csv".to_string()),
+        routine_diff: Some("target/test-output/routine/routines.diff".to_string()),
+        check: true,
+        ..Default::default()
+    };
+    let drift = compare(&snapshot, &options)?.unwrap();
+    assert_eq!(drift.counts.get(&DriftCategory::Routine), Some(&1));
+    assert_eq!(drift.counts.get(&DriftCategory::MissingRoutine), Some(&1));
+
+    let csv = read_to_string(options.routines.as_ref().unwrap())?;
+    assert!(csv.starts_with("Schema,Routine,Type,[uat ke],[uat tz],[uat ug]\n"));
+    assert!(csv.contains("\nmain,purge_orders,PROCEDURE,\"PROCEDURE(IN customer_id int)\n"));
+    assert!(csv.ends_with(" BEGIN END\",**\n"));
+    assert_eq!(csv.matches("\"**PROCEDURE").count(), 1);
+    assert!(csv.contains(
+        "\"**PROCEDURE(IN customer_id int)\n\
+         CREATE PROCEDURE `close_customer`(IN customer_id int) \
+         BEGIN DELETE FROM customer WHERE id = customer_id; END\""
+    ));
+
+    let diff = read_to_string(options.routine_diff.as_ref().unwrap())?;
+    assert!(diff.contains(
+        "--- PROCEDURE main.close_customer [uat ke] (reference)\n\
+         +++ PROCEDURE main.close_customer [uat ug]\n"
+    ));
+    assert!(diff.contains(
+        "\n PROCEDURE(IN customer_id int)\n\
+         -CREATE PROCEDURE `close_customer`(IN customer_id int) \
+         BEGIN UPDATE customer SET status = 0 WHERE id = customer_id;\n\
+         +CREATE PROCEDURE `close_customer`(IN customer_id int) \
+         BEGIN DELETE FROM customer WHERE id = customer_id;\n \
+         END\n"
+    ));
+    assert!(diff.contains("+++ PROCEDURE main.purge_orders [uat ug] MISSING\n"));
+    assert!(!diff.contains("[uat tz]"));
+    Ok(())
+}
+
+#[test]
+fn test_routines_sharing_name() -> Result<()> {
+    let snapshot = load_snapshot("tests/resources/snapshot")?;
+    let options = OutputOptions {
+        routines: Some("target/test-output/routine-name/diff-routines.csv".to_string()),
+        ..Default::default()
+    };
+    compare(&snapshot, &options)?;
+
+    let csv = read_to_string(options.routines.as_ref().unwrap())?;
+    assert!(csv.contains("\nmain,customer_count,FUNCTION,\"FUNCTION() RETURNS int\n"));
+    assert!(csv.contains("\nmain,customer_count,PROCEDURE,\"PROCEDURE(OUT total int)\n"));
+    assert!(!csv.contains("**FUNCTION"));
+    assert!(!csv.contains("**PROCEDURE(OUT total int)"));
+    Ok(())
+}
+
+#[test]
+fn test_normalize_definition() {
+    assert_eq!(
+        normalize_definition(
+            "CREATE DEFINER=`root`@`%` FUNCTION f() -- trailing\n\
+             RETURNS int # hash\n/* block\ncomment */ RETURN   '--  kept #' ;",
+            Engine::Mysql
+        ),
+        "CREATE FUNCTION f() RETURNS int RETURN '-- kept #' ;"
+    );
+    // escaped quotes don't end the text
+    assert_eq!(
+        normalize_definition("SELECT 'it\\'s -- kept' -- dropped", Engine::Mysql),
+        "SELECT 'it\\'s -- kept'"
+    );
+    // MySQL comments need a space after the dashes
+    assert_eq!(
+        normalize_definition("SET a = a--1;", Engine::Mysql),
+        "SET a = a--1;"
+    );
+    // `#` is an operator in PostgreSQL, and dollar quoted bodies are code
+    assert_eq!(
+        normalize_definition(
+            "CREATE FUNCTION f() RETURNS int AS $$\nBEGIN\n  -- note\n  RETURN 5 # 3;\nEND\n$$ LANGUAGE plpgsql",
+            Engine::Postgres
+        ),
+        "CREATE FUNCTION f() RETURNS int AS $$ BEGIN RETURN 5 # 3; END $$ LANGUAGE plpgsql"
+    );
+    // nested dollar quotes are text
+    assert_eq!(
+        normalize_definition(
+            "AS $body$ BEGIN EXECUTE $q$ -- kept $q$; END $body$",
+            Engine::Postgres
+        ),
+        "AS $body$ BEGIN EXECUTE $q$ -- kept $q$; END $body$"
+    );
+    // only the definer of the create header is dropped, quoted user names included
+    assert_eq!(
+        normalize_definition(
+            "CREATE DEFINER=`ops user`@`%` PROCEDURE p()\n\
+             BEGIN SELECT 'DEFINER=kept'; SET @x = 'a DEFINER=b'; END",
+            Engine::Mysql
+        ),
+        "CREATE PROCEDURE p() BEGIN SELECT 'DEFINER=kept'; SET @x = 'a DEFINER=b'; END"
+    );
+    assert_eq!(
+        normalize_definition(
+            "CREATE ALGORITHM=UNDEFINED DEFINER=`root`@`%` SQL SECURITY DEFINER VIEW v AS SELECT 1",
+            Engine::Mysql
+        ),
+        "CREATE ALGORITHM=UNDEFINED SQL SECURITY DEFINER VIEW v AS SELECT 1"
+    );
+    assert_eq!(
+        normalize_definition("CREATE TABLE t (definer=1)", Engine::Mysql),
+        "CREATE TABLE t (definer=1)"
+    );
+}
@@ -0,0 +1,52 @@
+use std::fs::read_to_string;
+
+use scomp::compare_mysql::{compare, load_snapshot, save_snapshot};
+use scomp::value_object::{OutputOptions, Result};
+
+#[test]
+fn test_load_snapshot() -> Result<()> {
+    let snapshot = load_snapshot("tests/resources/snapshot")?;
+    assert_eq!(snapshot.sources.len(), 3);
+    assert_eq!(snapshot.sources[0].password, "");
+    assert!(!snapshot.columns.is_empty());
+    assert!(!snapshot.indices.is_empty());
+    assert!(!snapshot.foreign_keys.is_empty());
+    Ok(())
+}
+
+#[test]
+fn test_save_snapshot() -> Result<()> {
+    let mut snapshot = load_snapshot("tests/resources/snapshot")?;
+    snapshot.sources[0].password = "secret".to_string();
+
+    let dir = "target/test-output/snapshot";
+    save_snapshot(dir, &snapshot)?;
+
+    let manifest = read_to_string(format!("{}/manifest.json", dir))?;
+    assert!(!manifest.contains("secret"));
+
+    let saved = load_snapshot(dir)?;
+    assert_eq!(saved.columns, snapshot.columns);
+    assert_eq!(saved.indices, snapshot.indices);
+    assert_eq!(saved.foreign_keys, snapshot.foreign_keys);
+    assert_eq!(saved.tables, snapshot.tables);
+    assert_eq!(saved.partitions, snapshot.partitions);
+    Ok(())
+}
+
+#[test]
+fn test_compare_snapshot() -> Result<()> {
+    let snapshot = load_snapshot("tests/resources/snapshot")?;
+    let options = OutputOptions {
+        columns: Some("target/test-output/snapshot-compare/diff-columns.csv".to_string()),
+        indices: Some("target/test-output/snapshot-compare/diff-indices.csv".to_string()),
+        foreign_keys: Some("target/test-output/snapshot-compare/diff-foreign-keys.csv".to_string()),
+        delimiter: b'\t',
+        ..Default::default()
+    };
+    compare(&snapshot, &options)?;
+
+    let columns = read_to_string(options.columns.as_ref().unwrap())?;
+    assert!(columns.contains("main\tcustomer\temail\t**varchar(64) NULL\tvarchar(128) NULL"));
+    Ok(())
+}
@@ -0,0 +1,217 @@
+use rusqlite::Connection;
+use std::fs::{self, read_to_string};
+use std::path::Path;
+use std::time::Duration;
+
+use scomp::compare_mysql::{collect, compare, read_config};
+use scomp::value_object::{CollectOptions, ConnectInfo, DriftCategory, OutputOptions, Result};
+
+static PREPARE: std::sync::Once = std::sync::Once::new();
+
+/// Create the databases of `tests/resources/datasource-sqlite.json` from `tests/resources/sqlite`
+pub fn prepare_databases() -> Result<Vec<ConnectInfo>> {
+    let configs = read_config("tests/resources/datasource-sqlite.json")?;
+    PREPARE.call_once(|| {
+        for config in &configs {
+            let path = config.database.as_ref().unwrap();
+            fs::create_dir_all(Path::new(path).parent().unwrap()).unwrap();
+            let _ = fs::remove_file(path);
+
+            let script = format!(
+                "tests/resources/sqlite/{}-{}.sql",
+                config.environment, config.country
+            );
+            let conn = Connection::open(path).unwrap();
+            conn.execute_batch(&read_to_string(script).unwrap())
+                .unwrap();
+        }
+    });
+    Ok(configs)
+}
+
+#[test]
+fn test_collect_sqlite() -> Result<()> {
+    let configs = prepare_databases()?;
+    let snapshot = collect(&configs[..1], &CollectOptions::default())?;
+
+    let email = snapshot
+        .columns
+        .iter()
+        .find(|c| c.table == "customer" && c.column_name == "email")
+        .unwrap();
+    assert_eq!(email.column_type, "varchar(64)");
+    assert_eq!(email.data_type, "varchar");
+    assert_eq!(email.nullable, "YES");
+
+    let currency = snapshot
+        .columns
+        .iter()
+        .find(|c| c.table == "order" && c.column_name == "currency")
+        .unwrap();
+    assert_eq!(currency.nullable, "NO");
+    assert_eq!(currency.default_value.as_deref(), Some("'KES'"));
+
+    let mut index: Vec<(i32, &str)> = snapshot
+        .indices
+        .iter()
+        .filter(|i| i.index_name == "idx_order_customer")
+        .map(|i| (i.sequence, i.column.as_str()))
+        .collect();
+    index.sort();
+    assert_eq!(index, vec![(1, "customer_id"), (2, "created_at")]);
+    assert!(snapshot
+        .indices
+        .iter()
+        .any(|i| i.table == "customer" && i.index_name == "PRIMARY" && i.column == "id"));
+    assert!(snapshot
+        .indices
+        .iter()
+        .any(|i| i.index_name == "idx_customer_email" && i.unique));
+
+    assert_eq!(snapshot.foreign_keys.len(), 1);
+    let foreign_key = &snapshot.foreign_keys[0];
+    assert_eq!(foreign_key.constraint_name, "fk_order_customer_id");
+    assert_eq!(foreign_key.referenced_schema, "main");
+    assert_eq!(foreign_key.referenced_table, "customer");
+    assert_eq!(foreign_key.referenced_column, "id");
+    assert_eq!(foreign_key.delete_rule, "CASCADE");
+
+    assert_eq!(snapshot.triggers.len(), 2);
+    let trigger = &snapshot.triggers[0];
+    assert_eq!(trigger.table, "customer");
+    assert_eq!(trigger.trigger_name, "trg_customer_delete");
+    assert_eq!(trigger.timing, "AFTER");
+    assert_eq!(trigger.event, "DELETE");
+    assert!(trigger.statement.starts_with("begin\n"));
+    // the columns and the condition are kept
+    let trigger = &snapshot.triggers[1];
+    assert_eq!(trigger.timing, "BEFORE");
+    assert_eq!(trigger.event, "UPDATE OF status");
+    assert!(trigger
+        .statement
+        .starts_with("for each row when old.status = 0\nbegin\n"));
+    Ok(())
+}
+
+#[test]
+fn test_compare_triggers() -> Result<()> {
+    let configs = prepare_databases()?;
+    let snapshot = collect(&configs, &CollectOptions::default())?;
+
+    let output = OutputOptions {
+        triggers: Some("target/test-output/sqlite/diff-triggers.csv".to_string()),
+        check: true,
+        ..Default::default()
+    };
+    let drift = compare(&snapshot, &output)?.unwrap();
+    assert_eq!(drift.counts.get(&DriftCategory::Trigger), Some(&2));
+    assert_eq!(drift.counts.get(&DriftCategory::MissingTrigger), Some(&2));
+
+    // tz lacks the triggers, ug runs a different body and checks a different condition
+    let triggers = read_to_string(output.triggers.as_ref().unwrap())?;
+    assert!(triggers.starts_with("Schema,Table,Trigger,[uat ke],[uat tz],[uat ug]\n"));
+    assert!(triggers.contains(
+        "main,customer,trg_customer_delete,\
+         \"AFTER DELETE begin delete from \"\"order\"\" where customer_id = old.id; end\",**,\
+         \"**AFTER DELETE begin update \"\"order\"\" set customer_id = 0 where customer_id = old.id; end\"\n"
+    ));
+    assert!(triggers.contains(
+        "main,customer,trg_customer_status,\
+         \"BEFORE UPDATE OF status for each row when old.status = 0 \
+         begin select raise(abort, 'closed customer'); end\",**,\
+         \"**BEFORE UPDATE OF status for each row when new.status = 0 \
+         begin select raise(abort, 'closed customer'); end\"\n"
+    ));
+    Ok(())
+}
+
+#[test]
+fn test_compare_views() -> Result<()> {
+    let configs = prepare_databases()?;
+    let snapshot = collect(&configs, &CollectOptions::default())?;
+    let view = snapshot
+        .views
+        .iter()
+        .find(|v| v.country == "ke" && v.view_name == "active_customer")
+        .unwrap();
+    assert_eq!(view.columns, "id, name, email");
+    assert!(!view.updatable);
+
+    let output = OutputOptions {
+        views: Some("target/test-output/sqlite/diff-views.csv".to_string()),
+        check: true,
+        ..Default::default()
+    };
+    let drift = compare(&snapshot, &output)?.unwrap();
+    assert_eq!(drift.counts.get(&DriftCategory::View), Some(&1));
+
+    // ug differs only in whitespace, tz lacks the email column
+    let views = read_to_string(output.views.as_ref().unwrap())?;
+    assert!(views.starts_with("Schema,View,[uat ke],[uat tz],[uat ug]\n"));
+    assert!(views.contains(
+        "main,active_customer,\"(id, name, email) READ ONLY\n\
+         CREATE VIEW active_customer as select id, name, email from customer where status = 1\",\
+         \"**(id, name) READ ONLY\n\
+         CREATE VIEW active_customer as select id, name from customer where status = 1\",\
+         \"(id, name, email) READ ONLY\n"
+    ));
+    Ok(())
+}
+
+#[test]
+fn test_collect_in_parallel() -> Result<()> {
+    let configs = prepare_databases()?;
+    let sequential = collect(
+        &configs,
+        &CollectOptions {
+            jobs: 1,
+            ..Default::default()
+        },
+    )?;
+    let parallel = collect(
+        &configs,
+        &CollectOptions {
+            jobs: 3,
+            ..Default::default()
+        },
+    )?;
+
+    assert_eq!(parallel.columns, sequential.columns);
+    assert_eq!(parallel.indices, sequential.indices);
+    assert_eq!(parallel.foreign_keys, sequential.foreign_keys);
+    Ok(())
+}
+
+#[test]
+fn test_collect_unreachable() -> Result<()> {
+    let mut configs = prepare_databases()?;
+    configs[1].database = Some("target/test-sqlite/missing.db".to_string());
+
+    let options = CollectOptions {
+        continue_on_error: true,
+        retries: 1,
+        retry_delay: Duration::from_millis(1),
+        ..Default::default()
+    };
+    let snapshot = collect(&configs, &options)?;
+    assert_eq!(snapshot.failures.len(), 1);
+    assert_eq!(snapshot.failures[0].country, "tz");
+    assert!(snapshot.columns.iter().all(|c| c.country != "tz"));
+
+    let output = OutputOptions {
+        columns: Some("target/test-output/unreachable/diff-columns.csv".to_string()),
+        indices: Some("target/test-output/unreachable/diff-indices.csv".to_string()),
+        foreign_keys: Some("target/test-output/unreachable/diff-foreign-keys.csv".to_string()),
+        delimiter: b'\t',
+        ..Default::default()
+    };
+    compare(&snapshot, &output)?;
+
+    // tz is neither missing nor counted in the most used definition
+    let columns = read_to_string(output.columns.as_ref().unwrap())?;
+    assert!(columns.contains("main\torder\tcurrency\tchar(3) 'KES'\tUNREACHABLE\tchar(3) 'KES'"));
+
+    // aborts without continue on error
+    assert!(collect(&configs, &CollectOptions::default()).is_err());
+    Ok(())
+}
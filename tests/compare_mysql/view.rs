@@ -0,0 +1,69 @@
+use std::fs::read_to_string;
+
+use scomp::compare_mysql::{compare, load_snapshot, normalize_view_definition};
+use scomp::value_object::{DriftCategory, Engine, OutputOptions, Result};
+
+#[test]
+fn test_normalize_view_definition() {
+    // as stored by MySQL
+    assert_eq!(
+        normalize_view_definition(
+            "select `main`.`customer`.`id` AS `id`,`main`.`customer`.`name` AS `name` \
+             from `main`.`customer` join `audit`.`log` on `audit`.`log`.`id` = `main`.`customer`.`id`",
+            "main",
+            Engine::Mysql
+        ),
+        "select customer.id AS id,customer.name AS name from customer \
+         join audit.log on audit.log.id = customer.id"
+    );
+    // names ending with the schema name are kept
+    assert_eq!(
+        normalize_view_definition("select domain.id from domain", "main", Engine::Mysql),
+        "select domain.id from domain"
+    );
+    // string literals aren't names
+    assert_eq!(
+        normalize_view_definition(
+            "select `main`.`t`.`id` AS `id` from `main`.`t` where `main`.`t`.`kind` = 'main.`x`'",
+            "main",
+            Engine::Mysql
+        ),
+        "select t.id AS id from t where t.kind = 'main.`x`'"
+    );
+}
+
+#[test]
+fn test_output_views() -> Result<()> {
+    let snapshot = load_snapshot("tests/resources/snapshot")?;
+    let options = OutputOptions {
+        views: Some("target/test-output/view/diff-views.csv".to_string()),
+        check: true,
+        ..Default::default()
+    };
+    let drift = compare(&snapshot, &options)?.unwrap();
+    assert_eq!(drift.counts.get(&DriftCategory::View), Some(&1));
+    assert_eq!(drift.counts.get(&DriftCategory::MissingView), Some(&1));
+
+    let views = read_to_string(options.views.as_ref().unwrap())?;
+    assert!(views.starts_with("Schema,View,[uat ke],[uat tz],[uat ug]\n"));
+    // ug lacks the view
+    assert!(views.contains(
+        "main,active_sessions,\"(id) SQL SECURITY DEFINER READ ONLY\n\
+         select session.id AS id from session where (session.expired_at > now())\",\
+         \"(id) SQL SECURITY DEFINER READ ONLY\n\
+         select session.id AS id from session where (session.expired_at > now())\",**\n"
+    ));
+    // tz stores the definition without backticks and schema, which is still the same
+    let customer_names = "\"(id, name) SQL SECURITY DEFINER UPDATABLE\n\
+         select customer.id AS id,customer.name AS name from customer\"";
+    assert!(views.contains(&format!(
+        "main,customer_names,{0},{0},{0}\n",
+        customer_names
+    )));
+    // tz runs the view as the invoker
+    assert!(views.contains(
+        ",\"**(id, status) WITH CASCADED CHECK OPTION SQL SECURITY INVOKER UPDATABLE\n\
+         select order.id AS id,order.status AS status from order where (order.status = 'open')\","
+    ));
+    Ok(())
+}
@@ -0,0 +1,74 @@
+use std::{fs::File, io::Read};
+
+use scomp::compare_mysql::{compare, load_snapshot};
+use scomp::value_object::{OutputOptions, Result};
+
+fn read_entry(archive: &mut zip::ZipArchive<File>, name: &str) -> Result<String> {
+    let mut content = String::new();
+    archive.by_name(name)?.read_to_string(&mut content)?;
+    Ok(content)
+}
+
+#[test]
+fn test_output_xlsx() -> Result<()> {
+    let snapshot = load_snapshot("tests/resources/snapshot")?;
+    let options = OutputOptions {
+        columns: Some("target/test-output/xlsx/diff-columns.csv".to_string()),
+        indices: Some("target/test-output/xlsx/diff-indices.csv".to_string()),
+        foreign_keys: Some("target/test-output/xlsx/diff-foreign-keys.csv".to_string()),
+        xlsx: Some("target/test-output/xlsx/diff.xlsx".to_string()),
+        ..Default::default()
+    };
+    compare(&snapshot, &options)?;
+
+    let mut archive = zip::ZipArchive::new(File::open(options.xlsx.as_ref().unwrap())?)?;
+    let workbook = read_entry(&mut archive, "xl/workbook.xml")?;
+    // the snapshot has no triggers, so no sheet for them
+    assert!(workbook.contains("name=\"Summary\""));
+    assert!(workbook.contains("name=\"Tables\""));
+    assert!(workbook.contains("name=\"Columns\""));
+    assert!(workbook.contains("name=\"Indices\""));
+    assert!(workbook.contains("name=\"Foreign Keys\""));
+    assert!(workbook.contains("name=\"Partitions\""));
+    assert!(!workbook.contains("name=\"Triggers\""));
+    assert!(workbook.contains("name=\"Views\""));
+    assert!(workbook.contains("name=\"Routines\""));
+    assert!(workbook.contains("name=\"Events\""));
+
+    let columns = read_entry(&mut archive, "xl/worksheets/sheet3.xml")?;
+    assert!(columns.contains("<pane ySplit=\"1\""));
+    assert!(columns.contains("<autoFilter ref=\"A1:F"));
+
+    let strings = read_entry(&mut archive, "xl/sharedStrings.xml")?;
+    assert!(strings.contains("varchar(64) NULL"));
+    assert!(!strings.contains("**"));
+    assert!(!strings.contains("identical objects omitted"));
+
+    let options = OutputOptions {
+        xlsx: Some("target/test-output/xlsx/diff-only.xlsx".to_string()),
+        only_diff: true,
+        ..options
+    };
+    compare(&snapshot, &options)?;
+    let mut archive = zip::ZipArchive::new(File::open(options.xlsx.as_ref().unwrap())?)?;
+    let strings = read_entry(&mut archive, "xl/sharedStrings.xml")?;
+    assert!(strings.contains("identical objects omitted"));
+    Ok(())
+}
+
+#[test]
+fn test_output_xlsx_long_cell() -> Result<()> {
+    let mut snapshot = load_snapshot("tests/resources/snapshot")?;
+    snapshot.routines[0].definition += "x".repeat(40_000).as_str();
+    let options = OutputOptions {
+        xlsx: Some("target/test-output/xlsx/long-cell.xlsx".to_string()),
+        ..Default::default()
+    };
+    compare(&snapshot, &options)?;
+
+    let mut archive = zip::ZipArchive::new(File::open(options.xlsx.as_ref().unwrap())?)?;
+    let strings = read_entry(&mut archive, "xl/sharedStrings.xml")?;
+    assert!(strings.contains("x…(truncated)</t>"));
+    assert!(!strings.contains(&"x".repeat(32_001)));
+    Ok(())
+}
@@ -0,0 +1,10 @@
+create table customer (
+    id integer primary key,
+    name varchar(64) not null,
+    email varchar(128),
+    status tinyint not null default 1
+);
+create unique index idx_customer_email on customer (email);
+
+create view active_customer as
+select id, name from customer where status = 1;
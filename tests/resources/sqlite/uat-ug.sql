@@ -0,0 +1,36 @@
+create table customer (
+    id integer primary key,
+    name varchar(64) not null,
+    email varchar(128),
+    status tinyint not null default 1,
+    legacy_code varchar(16)
+);
+create index idx_customer_legacy on customer (legacy_code);
+
+create table "order" (
+    id integer primary key,
+    customer_id bigint not null references customer (id) on delete restrict,
+    currency char(3) not null default 'KES',
+    created_at datetime not null default current_timestamp
+);
+create index idx_order_customer on "order" (customer_id, created_at);
+
+-- detach orders of deleted customers
+create trigger trg_customer_delete after delete on customer
+begin
+    update "order" set customer_id = 0 where customer_id = old.id;
+end;
+
+-- closed customers are read only
+create trigger trg_customer_status before update of status on customer
+for each row when new.status = 0
+begin
+    select raise(abort, 'closed customer');
+end;
+
+create view active_customer as
+select  id,
+       name,
+       email
+from customer
+where status = 1;